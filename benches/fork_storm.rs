@@ -0,0 +1,87 @@
+// Events per second under a synthetic fork storm
+//
+// Fills the receive buffer with the events of a burst of short lived
+// children, then times draining it with get_events and with recv_batch.
+// Listening needs CAP_NET_ADMIN, so run it as root:
+//
+//     cargo bench --bench fork_storm
+
+use std::time::{Duration, Instant};
+
+use cnproc::{PidMonitor, RecvBatch};
+
+const CHILDREN: usize = 20_000;
+
+fn main() {
+    println!("fork storm of {} children", CHILDREN);
+    run("get_events", |monitor| {
+        monitor.get_events().map(|events| events.len())
+    });
+    let mut batch = RecvBatch::new(64);
+    run("recv_batch(64)", |monitor| {
+        monitor.recv_batch(&mut batch, |_| {})
+    });
+}
+
+/// Runs a storm, then times `read` until the socket is drained
+fn run(name: &str, read: impl FnMut(&PidMonitor) -> cnproc::Result<usize>) {
+    let monitor = match subscribe() {
+        Ok(monitor) => monitor,
+        Err(err) => {
+            println!("{:>16}: skipped, {}", name, err);
+            return;
+        }
+    };
+    storm();
+    let (events, elapsed) = drain(&monitor, read);
+    println!(
+        "{:>16}: {} events in {:?}, {:.0} events/s, {} overflows",
+        name,
+        events,
+        elapsed,
+        events as f64 / elapsed.as_secs_f64(),
+        monitor.overflow_count()
+    );
+}
+
+fn subscribe() -> cnproc::Result<PidMonitor> {
+    let mut monitor = PidMonitor::builder()
+        .nonblocking(true)
+        .recv_buffer_size(256 << 20)
+        .force_recv_buffer_size(true)
+        .report_overflow(true)
+        .build()?;
+    monitor.listen()?;
+    Ok(monitor)
+}
+
+/// Forks children that exit right away, reaping each one
+fn storm() {
+    for _ in 0..CHILDREN {
+        match unsafe { libc::fork() } {
+            0 => unsafe { libc::_exit(0) },
+            -1 => panic!("fork: {}", std::io::Error::last_os_error()),
+            child => unsafe {
+                libc::waitpid(child, std::ptr::null_mut(), 0);
+            },
+        }
+    }
+}
+
+/// Reads until the socket would block, returning the number of events
+/// seen and the time it took
+fn drain(
+    monitor: &PidMonitor,
+    mut read: impl FnMut(&PidMonitor) -> cnproc::Result<usize>,
+) -> (usize, Duration) {
+    let start = Instant::now();
+    let mut events = 0;
+    loop {
+        match read(monitor) {
+            Ok(count) => events += count,
+            Err(err) if err.is_would_block() => break,
+            Err(err) => panic!("{}", err),
+        }
+    }
+    (events, start.elapsed())
+}
@@ -0,0 +1,11 @@
+// Whole datagrams, netlink headers included
+//
+//     cargo +nightly fuzz run parse
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|datagram: &[u8]| {
+    let _ = cnproc::parse(datagram);
+});
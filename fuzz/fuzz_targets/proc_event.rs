@@ -0,0 +1,28 @@
+// The cn_msg payload only, wrapped in valid netlink and connector
+// headers so the fuzzer spends its time in the proc_event decoding
+//
+//     cargo +nightly fuzz run proc_event
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+/// CN_IDX_PROC and CN_VAL_PROC
+const CN_PROC: [u32; 2] = [1, 1];
+
+fuzz_target!(|data: &[u8]| {
+    let data = &data[..data.len().min(u16::MAX as usize - 64)];
+    let len = 16 + 20 + data.len();
+    let mut datagram = Vec::with_capacity(len);
+    datagram.extend_from_slice(&(len as u32).to_ne_bytes());
+    // NLMSG_DONE, flags, seq and port id
+    datagram.extend_from_slice(&3u16.to_ne_bytes());
+    datagram.extend_from_slice(&[0; 10]);
+    for word in &[CN_PROC[0], CN_PROC[1], 0, 0] {
+        datagram.extend_from_slice(&word.to_ne_bytes());
+    }
+    datagram.extend_from_slice(&(data.len() as u16).to_ne_bytes());
+    datagram.extend_from_slice(&0u16.to_ne_bytes());
+    datagram.extend_from_slice(data);
+    let _ = cnproc::parse(&datagram);
+});
@@ -0,0 +1,109 @@
+// Runtime agnostic async support on top of async-io, enabled by the
+// `async-io` feature
+
+use std::collections::VecDeque;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use ::async_io::Async;
+use futures_core::Stream;
+
+use crate::{Error, Event, PidMonitor, Result};
+
+/// A [`PidMonitor`] driven by the async-io reactor, usable from smol
+/// or any other executor
+///
+/// Implements `Stream` as well. Leftover events of a datagram are
+/// queued here rather than in the monitor: they survive a cancelled
+/// `next_event()`, but [`AsyncIoPidMonitor::into_inner`] discards them.
+#[derive(Debug)]
+pub struct AsyncIoPidMonitor {
+    inner: Async<PidMonitor>,
+    buffered: VecDeque<Event>,
+}
+
+impl AsyncIoPidMonitor {
+    /// Switches `monitor` to non-blocking mode and registers it with
+    /// the async-io reactor
+    pub fn new(monitor: PidMonitor) -> Result<AsyncIoPidMonitor> {
+        // Async::new sets O_NONBLOCK behind the monitor's back, which
+        // would leave reads with a waker polling without a timeout
+        monitor.set_nonblocking(true)?;
+        let inner = Async::new(monitor).map_err(|err| Error::Io {
+            context: "registering with async-io",
+            source: err,
+        })?;
+        Ok(AsyncIoPidMonitor {
+            inner,
+            buffered: VecDeque::new(),
+        })
+    }
+
+    /// The wrapped monitor
+    pub fn get_ref(&self) -> &PidMonitor {
+        self.inner.get_ref()
+    }
+
+    /// Deregisters the monitor and gives it back, still non-blocking,
+    /// dropping any buffered events
+    pub fn into_inner(self) -> Result<PidMonitor> {
+        self.inner.into_inner().map_err(|err| Error::Io {
+            context: "deregistering from async-io",
+            source: err,
+        })
+    }
+
+    /// Waits for the next event
+    pub async fn next_event(&mut self) -> Result<Event> {
+        poll_fn(|cx| self.poll_event(cx)).await
+    }
+
+    /// Polls for the next event
+    pub fn poll_event(&mut self, cx: &mut Context<'_>) -> Poll<Result<Event>> {
+        loop {
+            if let Some(event) = self.buffered.pop_front() {
+                return Poll::Ready(Ok(event));
+            }
+            match self.inner.poll_readable(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(err)) => {
+                    return Poll::Ready(Err(Error::Io {
+                        context: "polling with async-io",
+                        source: err,
+                    }))
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+            match self.inner.get_ref().get_events() {
+                Ok(events) => self.buffered.extend(events),
+                Err(err) if err.is_would_block() => continue,
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+    }
+}
+
+impl Stream for AsyncIoPidMonitor {
+    type Item = Result<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Event>>> {
+        self.get_mut().poll_event(cx).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_inner_stays_nonblocking() {
+        let mut monitor = PidMonitor::new().unwrap();
+        monitor.waker().unwrap();
+        let monitor = AsyncIoPidMonitor::new(monitor)
+            .unwrap()
+            .into_inner()
+            .unwrap();
+        assert!(crate::testing::read_until_error(&monitor).is_would_block());
+    }
+}
@@ -0,0 +1,193 @@
+// Receiving many datagrams per system call with recvmmsg
+
+use std::os::unix::io::AsRawFd;
+
+use crate::parse::for_each_event;
+use crate::{Error, Event, PidMonitor, Result, Sender};
+
+/// Room for one datagram, proc connector messages are well below this
+const DATAGRAM_LEN: usize = 1024;
+
+/// Reusable buffers for [`PidMonitor::recv_batch`]
+#[derive(Debug)]
+pub struct RecvBatch {
+    buffer: Vec<u8>,
+    iovecs: Vec<libc::iovec>,
+    senders: Vec<Sender>,
+    headers: Vec<libc::mmsghdr>,
+}
+
+// the raw pointers in iovecs and headers are rebuilt before every use
+// and only ever point into buffer and senders
+unsafe impl Send for RecvBatch {}
+
+impl RecvBatch {
+    /// Buffers for receiving up to `datagrams` datagrams at once
+    pub fn new(datagrams: usize) -> RecvBatch {
+        let datagrams = datagrams.max(1);
+        RecvBatch {
+            buffer: vec![0; datagrams * DATAGRAM_LEN],
+            iovecs: Vec::with_capacity(datagrams),
+            senders: vec![Sender::new(); datagrams],
+            headers: Vec::with_capacity(datagrams),
+        }
+    }
+
+    /// Number of datagrams read per system call at most
+    pub fn capacity(&self) -> usize {
+        self.buffer.len() / DATAGRAM_LEN
+    }
+
+    /// Receives up to `capacity` datagrams, waiting for the first one
+    /// only, and returns how many were received
+    fn recv(&mut self, fd: libc::c_int, credentials: bool) -> Result<usize> {
+        self.iovecs.clear();
+        self.headers.clear();
+        for slot in self.buffer.chunks_mut(DATAGRAM_LEN) {
+            self.iovecs.push(libc::iovec {
+                iov_base: slot.as_mut_ptr() as _,
+                iov_len: std::mem::size_of_val(slot),
+            });
+        }
+        for (iovec, sender) in self.iovecs.iter_mut().zip(self.senders.iter_mut()) {
+            let mut header = unsafe { std::mem::zeroed::<libc::mmsghdr>() };
+            header.msg_hdr.msg_iov = iovec;
+            header.msg_hdr.msg_iovlen = 1;
+            sender.attach(&mut header.msg_hdr, credentials);
+            self.headers.push(header);
+        }
+        let received = unsafe {
+            libc::recvmmsg(
+                fd,
+                self.headers.as_mut_ptr(),
+                self.headers.len() as _,
+                libc::MSG_WAITFORONE,
+                std::ptr::null_mut(),
+            )
+        };
+        if received < 0 {
+            return Err(Error::last_os_error("recvmmsg"));
+        }
+        Ok(received as usize)
+    }
+
+    /// The `index`th datagram received by the last `recv`
+    fn datagram(&self, index: usize) -> &[u8] {
+        let start = index * DATAGRAM_LEN;
+        let len = (self.headers[index].msg_len as usize).min(DATAGRAM_LEN);
+        &self.buffer[start..start + len]
+    }
+
+    /// Where the `index`th datagram received by the last `recv` came from
+    fn sender(&self, index: usize) -> (&Sender, &libc::msghdr) {
+        (&self.senders[index], &self.headers[index].msg_hdr)
+    }
+
+    /// Fills the buffers as if `recv` had received `datagrams` from
+    /// the kernel, and returns how many that is
+    #[cfg(test)]
+    fn fill(&mut self, datagrams: &[Vec<u8>]) -> usize {
+        assert!(datagrams.len() <= self.capacity());
+        self.headers.clear();
+        for (index, datagram) in datagrams.iter().enumerate() {
+            let start = index * DATAGRAM_LEN;
+            self.buffer[start..start + datagram.len()].copy_from_slice(datagram);
+            let mut header = unsafe { std::mem::zeroed::<libc::mmsghdr>() };
+            self.senders[index].attach(&mut header.msg_hdr, false);
+            self.senders[index].addr.nl_family = libc::AF_NETLINK as u16;
+            self.senders[index].addr.nl_pid = 0;
+            header.msg_len = datagram.len() as _;
+            self.headers.push(header);
+        }
+        datagrams.len()
+    }
+}
+
+impl PidMonitor {
+    /// Receives as many datagrams as `batch` has room for in one
+    /// system call and hands every event to `sink`, returning how many
+    /// events that was
+    ///
+    /// Waits like [`PidMonitor::get_events`] if nothing is queued,
+    /// but allocates nothing, so it suits high event rates.
+    ///
+    /// A datagram the kernel reports an error in doesn't stop the
+    /// others from being read, their events still reach `sink` and the
+    /// first error is returned afterwards.
+    pub fn recv_batch(&self, batch: &mut RecvBatch, mut sink: impl FnMut(Event)) -> Result<usize> {
+        self.wait_for_events()?;
+        let received = match batch.recv(self.as_raw_fd(), self.verify_credentials) {
+            Err(err) if self.is_overflow(&err) => {
+                sink(self.overflow_event());
+                return Ok(1);
+            }
+            received => received?,
+        };
+        self.deliver_batch(batch, received, sink)
+    }
+
+    /// Hands the events of the first `received` datagrams in `batch`
+    /// to `sink`
+    fn deliver_batch(
+        &self,
+        batch: &RecvBatch,
+        received: usize,
+        mut sink: impl FnMut(Event),
+    ) -> Result<usize> {
+        let mut events = 0;
+        let mut first_err = None;
+        for index in 0..received {
+            let (sender, hdr) = batch.sender(index);
+            if !self.check_sender(sender, hdr) {
+                continue;
+            }
+            let delivered = for_each_event(batch.datagram(index), |_, event| {
+                if self.wants(&event) {
+                    events += 1;
+                    sink(event)
+                }
+            });
+            if let Err(err) = delivered {
+                first_err.get_or_insert(err);
+            }
+        }
+        match first_err {
+            Some(err) => Err(err),
+            None => Ok(events),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fork;
+    use crate::{encode, DatagramBuilder, ProcEvent};
+
+    #[test]
+    fn delivers_every_datagram_of_a_batch() {
+        let mut monitor = PidMonitor::new().unwrap();
+        // as if subscribed, events are dropped otherwise
+        monitor.listening = true;
+        let mut batch = RecvBatch::new(4);
+        let received = batch.fill(&[
+            encode(&fork(10)).unwrap(),
+            DatagramBuilder::new().error(libc::EINVAL).build(),
+            encode(&fork(11)).unwrap(),
+        ]);
+        let mut pids = Vec::new();
+        let delivered = monitor.deliver_batch(&batch, received, |event| {
+            if let ProcEvent::Fork { child_pid, .. } = event.kind {
+                pids.push(child_pid)
+            }
+        });
+        assert_eq!(pids, [10, 11]);
+        assert_eq!(delivered.unwrap_err().raw_os_error(), Some(libc::EINVAL));
+
+        let received = batch.fill(&[encode(&fork(12)).unwrap(), encode(&fork(13)).unwrap()]);
+        assert_eq!(monitor.deliver_batch(&batch, received, |_| {}).unwrap(), 2);
+        assert_eq!(monitor.spoofed_count(), 0);
+        // never subscribed, so there is nothing to unsubscribe on drop
+        monitor.listening = false;
+    }
+}
@@ -151,7 +151,7 @@ fn bindgen_test_layout___kernel_fd_set() {
         concat!("Alignment of ", stringify!(__kernel_fd_set))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<__kernel_fd_set>())).fds_bits as *const _ as usize },
+        ::core::mem::offset_of!(__kernel_fd_set, fds_bits),
         0usize,
         concat!(
             "Offset of field: ",
@@ -200,7 +200,7 @@ fn bindgen_test_layout___kernel_fsid_t() {
         concat!("Alignment of ", stringify!(__kernel_fsid_t))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<__kernel_fsid_t>())).val as *const _ as usize },
+        ::core::mem::offset_of!(__kernel_fsid_t, val),
         0usize,
         concat!(
             "Offset of field: ",
@@ -245,7 +245,7 @@ fn bindgen_test_layout_cb_id() {
         concat!("Alignment of ", stringify!(cb_id))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<cb_id>())).idx as *const _ as usize },
+        ::core::mem::offset_of!(cb_id, idx),
         0usize,
         concat!(
             "Offset of field: ",
@@ -255,7 +255,7 @@ fn bindgen_test_layout_cb_id() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<cb_id>())).val as *const _ as usize },
+        ::core::mem::offset_of!(cb_id, val),
         4usize,
         concat!(
             "Offset of field: ",
@@ -288,7 +288,7 @@ fn bindgen_test_layout_cn_msg() {
         concat!("Alignment of ", stringify!(cn_msg))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<cn_msg>())).id as *const _ as usize },
+        ::core::mem::offset_of!(cn_msg, id),
         0usize,
         concat!(
             "Offset of field: ",
@@ -298,7 +298,7 @@ fn bindgen_test_layout_cn_msg() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<cn_msg>())).seq as *const _ as usize },
+        ::core::mem::offset_of!(cn_msg, seq),
         8usize,
         concat!(
             "Offset of field: ",
@@ -308,7 +308,7 @@ fn bindgen_test_layout_cn_msg() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<cn_msg>())).ack as *const _ as usize },
+        ::core::mem::offset_of!(cn_msg, ack),
         12usize,
         concat!(
             "Offset of field: ",
@@ -318,7 +318,7 @@ fn bindgen_test_layout_cn_msg() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<cn_msg>())).len as *const _ as usize },
+        ::core::mem::offset_of!(cn_msg, len),
         16usize,
         concat!(
             "Offset of field: ",
@@ -328,7 +328,7 @@ fn bindgen_test_layout_cn_msg() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<cn_msg>())).flags as *const _ as usize },
+        ::core::mem::offset_of!(cn_msg, flags),
         18usize,
         concat!(
             "Offset of field: ",
@@ -338,7 +338,7 @@ fn bindgen_test_layout_cn_msg() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<cn_msg>())).data as *const _ as usize },
+        ::core::mem::offset_of!(cn_msg, data),
         20usize,
         concat!(
             "Offset of field: ",
@@ -379,7 +379,7 @@ fn bindgen_test_layout_sysinfo() {
         concat!("Alignment of ", stringify!(sysinfo))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).uptime as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, uptime),
         0usize,
         concat!(
             "Offset of field: ",
@@ -389,7 +389,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).loads as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, loads),
         8usize,
         concat!(
             "Offset of field: ",
@@ -399,7 +399,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).totalram as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, totalram),
         32usize,
         concat!(
             "Offset of field: ",
@@ -409,7 +409,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).freeram as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, freeram),
         40usize,
         concat!(
             "Offset of field: ",
@@ -419,7 +419,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).sharedram as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, sharedram),
         48usize,
         concat!(
             "Offset of field: ",
@@ -429,7 +429,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).bufferram as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, bufferram),
         56usize,
         concat!(
             "Offset of field: ",
@@ -439,7 +439,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).totalswap as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, totalswap),
         64usize,
         concat!(
             "Offset of field: ",
@@ -449,7 +449,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).freeswap as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, freeswap),
         72usize,
         concat!(
             "Offset of field: ",
@@ -459,7 +459,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).procs as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, procs),
         80usize,
         concat!(
             "Offset of field: ",
@@ -469,7 +469,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).pad as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, pad),
         82usize,
         concat!(
             "Offset of field: ",
@@ -479,7 +479,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).totalhigh as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, totalhigh),
         88usize,
         concat!(
             "Offset of field: ",
@@ -489,7 +489,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).freehigh as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, freehigh),
         96usize,
         concat!(
             "Offset of field: ",
@@ -499,7 +499,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>())).mem_unit as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, mem_unit),
         104usize,
         concat!(
             "Offset of field: ",
@@ -509,7 +509,7 @@ fn bindgen_test_layout_sysinfo() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sysinfo>()))._f as *const _ as usize },
+        ::core::mem::offset_of!(sysinfo, _f),
         108usize,
         concat!(
             "Offset of field: ",
@@ -557,10 +557,7 @@ fn bindgen_test_layout___kernel_sockaddr_storage__bindgen_ty_1__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<__kernel_sockaddr_storage__bindgen_ty_1__bindgen_ty_1>()))
-                .ss_family as *const _ as usize
-        },
+        ::core::mem::offset_of!(__kernel_sockaddr_storage__bindgen_ty_1__bindgen_ty_1, ss_family),
         0usize,
         concat!(
             "Offset of field: ",
@@ -570,10 +567,7 @@ fn bindgen_test_layout___kernel_sockaddr_storage__bindgen_ty_1__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<__kernel_sockaddr_storage__bindgen_ty_1__bindgen_ty_1>()))
-                .__data as *const _ as usize
-        },
+        ::core::mem::offset_of!(__kernel_sockaddr_storage__bindgen_ty_1__bindgen_ty_1, __data),
         2usize,
         concat!(
             "Offset of field: ",
@@ -602,10 +596,7 @@ fn bindgen_test_layout___kernel_sockaddr_storage__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<__kernel_sockaddr_storage__bindgen_ty_1>())).__align as *const _
-                as usize
-        },
+        ::core::mem::offset_of!(__kernel_sockaddr_storage__bindgen_ty_1, __align),
         0usize,
         concat!(
             "Offset of field: ",
@@ -649,7 +640,7 @@ fn bindgen_test_layout_sockaddr_nl() {
         concat!("Alignment of ", stringify!(sockaddr_nl))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sockaddr_nl>())).nl_family as *const _ as usize },
+        ::core::mem::offset_of!(sockaddr_nl, nl_family),
         0usize,
         concat!(
             "Offset of field: ",
@@ -659,7 +650,7 @@ fn bindgen_test_layout_sockaddr_nl() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sockaddr_nl>())).nl_pad as *const _ as usize },
+        ::core::mem::offset_of!(sockaddr_nl, nl_pad),
         2usize,
         concat!(
             "Offset of field: ",
@@ -669,7 +660,7 @@ fn bindgen_test_layout_sockaddr_nl() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sockaddr_nl>())).nl_pid as *const _ as usize },
+        ::core::mem::offset_of!(sockaddr_nl, nl_pid),
         4usize,
         concat!(
             "Offset of field: ",
@@ -679,7 +670,7 @@ fn bindgen_test_layout_sockaddr_nl() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<sockaddr_nl>())).nl_groups as *const _ as usize },
+        ::core::mem::offset_of!(sockaddr_nl, nl_groups),
         8usize,
         concat!(
             "Offset of field: ",
@@ -711,7 +702,7 @@ fn bindgen_test_layout_nlmsghdr() {
         concat!("Alignment of ", stringify!(nlmsghdr))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nlmsghdr>())).nlmsg_len as *const _ as usize },
+        ::core::mem::offset_of!(nlmsghdr, nlmsg_len),
         0usize,
         concat!(
             "Offset of field: ",
@@ -721,7 +712,7 @@ fn bindgen_test_layout_nlmsghdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nlmsghdr>())).nlmsg_type as *const _ as usize },
+        ::core::mem::offset_of!(nlmsghdr, nlmsg_type),
         4usize,
         concat!(
             "Offset of field: ",
@@ -731,7 +722,7 @@ fn bindgen_test_layout_nlmsghdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nlmsghdr>())).nlmsg_flags as *const _ as usize },
+        ::core::mem::offset_of!(nlmsghdr, nlmsg_flags),
         6usize,
         concat!(
             "Offset of field: ",
@@ -741,7 +732,7 @@ fn bindgen_test_layout_nlmsghdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nlmsghdr>())).nlmsg_seq as *const _ as usize },
+        ::core::mem::offset_of!(nlmsghdr, nlmsg_seq),
         8usize,
         concat!(
             "Offset of field: ",
@@ -751,7 +742,7 @@ fn bindgen_test_layout_nlmsghdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nlmsghdr>())).nlmsg_pid as *const _ as usize },
+        ::core::mem::offset_of!(nlmsghdr, nlmsg_pid),
         12usize,
         concat!(
             "Offset of field: ",
@@ -780,7 +771,7 @@ fn bindgen_test_layout_nlmsgerr() {
         concat!("Alignment of ", stringify!(nlmsgerr))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nlmsgerr>())).error as *const _ as usize },
+        ::core::mem::offset_of!(nlmsgerr, error),
         0usize,
         concat!(
             "Offset of field: ",
@@ -790,7 +781,7 @@ fn bindgen_test_layout_nlmsgerr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nlmsgerr>())).msg as *const _ as usize },
+        ::core::mem::offset_of!(nlmsgerr, msg),
         4usize,
         concat!(
             "Offset of field: ",
@@ -825,7 +816,7 @@ fn bindgen_test_layout_nl_pktinfo() {
         concat!("Alignment of ", stringify!(nl_pktinfo))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_pktinfo>())).group as *const _ as usize },
+        ::core::mem::offset_of!(nl_pktinfo, group),
         0usize,
         concat!(
             "Offset of field: ",
@@ -856,7 +847,7 @@ fn bindgen_test_layout_nl_mmap_req() {
         concat!("Alignment of ", stringify!(nl_mmap_req))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_req>())).nm_block_size as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_req, nm_block_size),
         0usize,
         concat!(
             "Offset of field: ",
@@ -866,7 +857,7 @@ fn bindgen_test_layout_nl_mmap_req() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_req>())).nm_block_nr as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_req, nm_block_nr),
         4usize,
         concat!(
             "Offset of field: ",
@@ -876,7 +867,7 @@ fn bindgen_test_layout_nl_mmap_req() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_req>())).nm_frame_size as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_req, nm_frame_size),
         8usize,
         concat!(
             "Offset of field: ",
@@ -886,7 +877,7 @@ fn bindgen_test_layout_nl_mmap_req() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_req>())).nm_frame_nr as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_req, nm_frame_nr),
         12usize,
         concat!(
             "Offset of field: ",
@@ -919,7 +910,7 @@ fn bindgen_test_layout_nl_mmap_hdr() {
         concat!("Alignment of ", stringify!(nl_mmap_hdr))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_hdr>())).nm_status as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_hdr, nm_status),
         0usize,
         concat!(
             "Offset of field: ",
@@ -929,7 +920,7 @@ fn bindgen_test_layout_nl_mmap_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_hdr>())).nm_len as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_hdr, nm_len),
         4usize,
         concat!(
             "Offset of field: ",
@@ -939,7 +930,7 @@ fn bindgen_test_layout_nl_mmap_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_hdr>())).nm_group as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_hdr, nm_group),
         8usize,
         concat!(
             "Offset of field: ",
@@ -949,7 +940,7 @@ fn bindgen_test_layout_nl_mmap_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_hdr>())).nm_pid as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_hdr, nm_pid),
         12usize,
         concat!(
             "Offset of field: ",
@@ -959,7 +950,7 @@ fn bindgen_test_layout_nl_mmap_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_hdr>())).nm_uid as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_hdr, nm_uid),
         16usize,
         concat!(
             "Offset of field: ",
@@ -969,7 +960,7 @@ fn bindgen_test_layout_nl_mmap_hdr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nl_mmap_hdr>())).nm_gid as *const _ as usize },
+        ::core::mem::offset_of!(nl_mmap_hdr, nm_gid),
         20usize,
         concat!(
             "Offset of field: ",
@@ -1007,7 +998,7 @@ fn bindgen_test_layout_nlattr() {
         concat!("Alignment of ", stringify!(nlattr))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nlattr>())).nla_len as *const _ as usize },
+        ::core::mem::offset_of!(nlattr, nla_len),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1017,7 +1008,7 @@ fn bindgen_test_layout_nlattr() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nlattr>())).nla_type as *const _ as usize },
+        ::core::mem::offset_of!(nlattr, nla_type),
         2usize,
         concat!(
             "Offset of field: ",
@@ -1046,7 +1037,7 @@ fn bindgen_test_layout_nla_bitfield32() {
         concat!("Alignment of ", stringify!(nla_bitfield32))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nla_bitfield32>())).value as *const _ as usize },
+        ::core::mem::offset_of!(nla_bitfield32, value),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1056,7 +1047,7 @@ fn bindgen_test_layout_nla_bitfield32() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<nla_bitfield32>())).selector as *const _ as usize },
+        ::core::mem::offset_of!(nla_bitfield32, selector),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1069,25 +1060,65 @@ fn bindgen_test_layout_nla_bitfield32() {
 pub const PROC_CN_MCAST_LISTEN: proc_cn_mcast_op = 1;
 pub const PROC_CN_MCAST_IGNORE: proc_cn_mcast_op = 2;
 pub type proc_cn_mcast_op = u32;
+pub const PROC_EVENT_NONE: proc_cn_event = 0;
+pub const PROC_EVENT_FORK: proc_cn_event = 1;
+pub const PROC_EVENT_EXEC: proc_cn_event = 2;
+pub const PROC_EVENT_UID: proc_cn_event = 4;
+pub const PROC_EVENT_GID: proc_cn_event = 64;
+pub const PROC_EVENT_SID: proc_cn_event = 128;
+pub const PROC_EVENT_PTRACE: proc_cn_event = 256;
+pub const PROC_EVENT_COMM: proc_cn_event = 512;
+pub const PROC_EVENT_NONZERO_EXIT: proc_cn_event = 536870912;
+pub const PROC_EVENT_COREDUMP: proc_cn_event = 1073741824;
+pub const PROC_EVENT_EXIT: proc_cn_event = 2147483648;
+pub type proc_cn_event = u32;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct proc_input {
+    pub mcast_op: proc_cn_mcast_op,
+    pub event_type: proc_cn_event,
+}
+#[test]
+fn bindgen_test_layout_proc_input() {
+    assert_eq!(
+        ::core::mem::size_of::<proc_input>(),
+        8usize,
+        concat!("Size of: ", stringify!(proc_input))
+    );
+    assert_eq!(
+        ::core::mem::align_of::<proc_input>(),
+        4usize,
+        concat!("Alignment of ", stringify!(proc_input))
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(proc_input, mcast_op),
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(proc_input),
+            "::",
+            stringify!(mcast_op)
+        )
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(proc_input, event_type),
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(proc_input),
+            "::",
+            stringify!(event_type)
+        )
+    );
+}
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct proc_event {
-    pub what: proc_event_what,
+    pub what: proc_cn_event,
     pub cpu: __u32,
     pub timestamp_ns: __u64,
     pub event_data: proc_event__bindgen_ty_1,
 }
-pub const PROC_EVENT_NONE: proc_event_what = 0;
-pub const PROC_EVENT_FORK: proc_event_what = 1;
-pub const PROC_EVENT_EXEC: proc_event_what = 2;
-pub const PROC_EVENT_UID: proc_event_what = 4;
-pub const PROC_EVENT_GID: proc_event_what = 64;
-pub const PROC_EVENT_SID: proc_event_what = 128;
-pub const PROC_EVENT_PTRACE: proc_event_what = 256;
-pub const PROC_EVENT_COMM: proc_event_what = 512;
-pub const PROC_EVENT_COREDUMP: proc_event_what = 1073741824;
-pub const PROC_EVENT_EXIT: proc_event_what = 2147483648;
-pub type proc_event_what = u32;
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub union proc_event__bindgen_ty_1 {
@@ -1126,10 +1157,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1__bindgen_ty_1>())).err as *const _
-                as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1__bindgen_ty_1, err),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1166,10 +1194,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_fork_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_fork_proc_event>())).parent_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_fork_proc_event, parent_pid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1179,10 +1204,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_fork_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_fork_proc_event>())).parent_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_fork_proc_event, parent_tgid),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1192,10 +1214,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_fork_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_fork_proc_event>())).child_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_fork_proc_event, child_pid),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1205,10 +1224,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_fork_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_fork_proc_event>())).child_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_fork_proc_event, child_tgid),
         12usize,
         concat!(
             "Offset of field: ",
@@ -1243,10 +1259,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_exec_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_exec_proc_event>())).process_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_exec_proc_event, process_pid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1256,10 +1269,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_exec_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_exec_proc_event>())).process_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_exec_proc_event, process_tgid),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1303,10 +1313,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_1>())).ruid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_1, ruid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1316,10 +1323,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_1>())).rgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_1, rgid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1355,10 +1359,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_2>())).euid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_2, euid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1368,10 +1369,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_2() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_2>())).egid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_2, egid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1400,10 +1398,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_id_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_id_proc_event>())).process_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_id_proc_event, process_pid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1413,10 +1408,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_id_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_id_proc_event>())).process_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_id_proc_event, process_tgid),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1426,10 +1418,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_id_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_id_proc_event>())).r as *const _
-                as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_id_proc_event, r),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1439,10 +1428,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_id_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_id_proc_event>())).e as *const _
-                as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_id_proc_event, e),
         12usize,
         concat!(
             "Offset of field: ",
@@ -1477,10 +1463,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_sid_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_sid_proc_event>())).process_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_sid_proc_event, process_pid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1490,10 +1473,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_sid_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_sid_proc_event>())).process_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_sid_proc_event, process_tgid),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1530,10 +1510,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_ptrace_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_ptrace_proc_event>())).process_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_ptrace_proc_event, process_pid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1543,10 +1520,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_ptrace_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_ptrace_proc_event>())).process_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_ptrace_proc_event, process_tgid),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1556,10 +1530,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_ptrace_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_ptrace_proc_event>())).tracer_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_ptrace_proc_event, tracer_pid),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1569,10 +1540,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_ptrace_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_ptrace_proc_event>())).tracer_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_ptrace_proc_event, tracer_tgid),
         12usize,
         concat!(
             "Offset of field: ",
@@ -1608,10 +1576,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_comm_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_comm_proc_event>())).process_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_comm_proc_event, process_pid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1621,10 +1586,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_comm_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_comm_proc_event>())).process_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_comm_proc_event, process_tgid),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1634,10 +1596,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_comm_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_comm_proc_event>())).comm as *const _
-                as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_comm_proc_event, comm),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1674,10 +1633,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_coredump_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_coredump_proc_event>())).process_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_coredump_proc_event, process_pid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1687,10 +1643,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_coredump_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_coredump_proc_event>())).process_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_coredump_proc_event, process_tgid),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1700,10 +1653,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_coredump_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_coredump_proc_event>())).parent_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_coredump_proc_event, parent_pid),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1713,10 +1663,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_coredump_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_coredump_proc_event>())).parent_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_coredump_proc_event, parent_tgid),
         12usize,
         concat!(
             "Offset of field: ",
@@ -1755,10 +1702,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_exit_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_exit_proc_event>())).process_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_exit_proc_event, process_pid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1768,10 +1712,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_exit_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_exit_proc_event>())).process_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_exit_proc_event, process_tgid),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1781,10 +1722,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_exit_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_exit_proc_event>())).exit_code
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_exit_proc_event, exit_code),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1794,10 +1732,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_exit_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_exit_proc_event>())).exit_signal
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_exit_proc_event, exit_signal),
         12usize,
         concat!(
             "Offset of field: ",
@@ -1807,10 +1742,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_exit_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_exit_proc_event>())).parent_pid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_exit_proc_event, parent_pid),
         16usize,
         concat!(
             "Offset of field: ",
@@ -1820,10 +1752,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1_exit_proc_event() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1_exit_proc_event>())).parent_tgid
-                as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1_exit_proc_event, parent_tgid),
         20usize,
         concat!(
             "Offset of field: ",
@@ -1846,7 +1775,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1() {
         concat!("Alignment of ", stringify!(proc_event__bindgen_ty_1))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event__bindgen_ty_1>())).ack as *const _ as usize },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1, ack),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1856,7 +1785,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event__bindgen_ty_1>())).fork as *const _ as usize },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1, fork),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1866,7 +1795,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event__bindgen_ty_1>())).exec as *const _ as usize },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1, exec),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1876,7 +1805,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event__bindgen_ty_1>())).id as *const _ as usize },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1, id),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1886,7 +1815,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event__bindgen_ty_1>())).sid as *const _ as usize },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1, sid),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1896,9 +1825,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1>())).ptrace as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1, ptrace),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1908,7 +1835,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event__bindgen_ty_1>())).comm as *const _ as usize },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1, comm),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1918,9 +1845,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe {
-            &(*(::core::ptr::null::<proc_event__bindgen_ty_1>())).coredump as *const _ as usize
-        },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1, coredump),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1930,7 +1855,7 @@ fn bindgen_test_layout_proc_event__bindgen_ty_1() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event__bindgen_ty_1>())).exit as *const _ as usize },
+        ::core::mem::offset_of!(proc_event__bindgen_ty_1, exit),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1953,7 +1878,7 @@ fn bindgen_test_layout_proc_event() {
         concat!("Alignment of ", stringify!(proc_event))
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event>())).what as *const _ as usize },
+        ::core::mem::offset_of!(proc_event, what),
         0usize,
         concat!(
             "Offset of field: ",
@@ -1963,7 +1888,7 @@ fn bindgen_test_layout_proc_event() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event>())).cpu as *const _ as usize },
+        ::core::mem::offset_of!(proc_event, cpu),
         4usize,
         concat!(
             "Offset of field: ",
@@ -1973,7 +1898,7 @@ fn bindgen_test_layout_proc_event() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event>())).timestamp_ns as *const _ as usize },
+        ::core::mem::offset_of!(proc_event, timestamp_ns),
         8usize,
         concat!(
             "Offset of field: ",
@@ -1983,7 +1908,7 @@ fn bindgen_test_layout_proc_event() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::core::ptr::null::<proc_event>())).event_data as *const _ as usize },
+        ::core::mem::offset_of!(proc_event, event_data),
         16usize,
         concat!(
             "Offset of field: ",
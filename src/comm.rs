@@ -0,0 +1,81 @@
+// The task name carried by PROC_EVENT_COMM
+
+/// Length of a task name in the kernel, including the trailing NUL
+pub const TASK_COMM_LEN: usize = 16;
+
+/// A task name as set by exec or `prctl(PR_SET_NAME)`
+///
+/// The kernel sends a NUL padded buffer with no encoding guarantees,
+/// this keeps the raw buffer and offers both a byte and a string view
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProcessName {
+    raw: [u8; TASK_COMM_LEN],
+}
+
+impl ProcessName {
+    /// Wraps a raw, NUL padded name
+    pub fn from_raw(raw: [u8; TASK_COMM_LEN]) -> ProcessName {
+        ProcessName { raw }
+    }
+
+    /// The buffer exactly as sent by the kernel
+    pub fn raw(&self) -> &[u8; TASK_COMM_LEN] {
+        &self.raw
+    }
+
+    /// The name up to the first NUL
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = self
+            .raw
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(TASK_COMM_LEN);
+        &self.raw[..len]
+    }
+
+    /// The name decoded as UTF-8, with invalid sequences replaced
+    /// by U+FFFD
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf8_lossy(self.as_bytes()).into_owned()
+    }
+}
+
+impl From<[u8; TASK_COMM_LEN]> for ProcessName {
+    fn from(raw: [u8; TASK_COMM_LEN]) -> ProcessName {
+        ProcessName::from_raw(raw)
+    }
+}
+
+impl std::fmt::Debug for ProcessName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+impl std::fmt::Display for ProcessName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_names() {
+        let mut raw = [0u8; TASK_COMM_LEN];
+        raw[..4].copy_from_slice(b"bash");
+        let name = ProcessName::from_raw(raw);
+        assert_eq!(name.as_bytes(), b"bash");
+        assert_eq!(name.to_string_lossy(), "bash");
+
+        let full = ProcessName::from_raw(*b"0123456789abcdef");
+        assert_eq!(full.as_bytes().len(), TASK_COMM_LEN);
+
+        raw[..3].copy_from_slice(&[b'a', 0xff, b'b']);
+        let invalid = ProcessName::from_raw(raw);
+        assert_eq!(invalid.as_bytes(), &[b'a', 0xff, b'b', b'h']);
+        assert_eq!(invalid.to_string_lossy(), "a\u{fffd}bh");
+    }
+}
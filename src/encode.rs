@@ -0,0 +1,360 @@
+// Building proc connector datagrams, for tests and simulations
+//
+// Every message is assembled from the kernel structs in binding, so the
+// bytes are laid out exactly as the kernel sends them and `parse` reads
+// them back as it would read the real thing.
+
+use std::mem::size_of;
+
+use crate::binding::{
+    self, cn_msg, nlmsgerr, nlmsghdr, proc_event, proc_event__bindgen_ty_1__bindgen_ty_1,
+    proc_event__bindgen_ty_1_comm_proc_event, proc_event__bindgen_ty_1_coredump_proc_event,
+    proc_event__bindgen_ty_1_exec_proc_event, proc_event__bindgen_ty_1_exit_proc_event,
+    proc_event__bindgen_ty_1_fork_proc_event, proc_event__bindgen_ty_1_id_proc_event,
+    proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_1,
+    proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_2,
+    proc_event__bindgen_ty_1_ptrace_proc_event, proc_event__bindgen_ty_1_sid_proc_event,
+    CN_IDX_PROC,
+};
+use crate::{nlmsg_align, nlmsg_length, Error, Event, ProcEvent, Result, TASK_COMM_LEN};
+
+/// Builds a datagram in the wire format of the proc connector, one
+/// netlink message per event or control message
+///
+/// ```
+/// use cnproc::{DatagramBuilder, Event, ProcEvent};
+///
+/// let exec = Event {
+///     cpu: 0,
+///     timestamp_ns: 0,
+///     kind: ProcEvent::Exec {
+///         process_pid: 42,
+///         process_tgid: 42,
+///     },
+/// };
+/// let datagram = DatagramBuilder::new().event(&exec)?.build();
+/// assert_eq!(cnproc::parse(&datagram)?, vec![exec]);
+/// # Ok::<(), cnproc::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DatagramBuilder {
+    buf: Vec<u8>,
+    seq: u32,
+    ack: u32,
+}
+
+impl DatagramBuilder {
+    /// An empty datagram
+    pub fn new() -> DatagramBuilder {
+        DatagramBuilder::default()
+    }
+
+    /// Sequence number of the next event, incremented after each one
+    /// like the kernel's per-CPU counter
+    pub fn seq(mut self, seq: u32) -> DatagramBuilder {
+        self.seq = seq;
+        self
+    }
+
+    /// cn_msg ack of the following events, 0 for plain events and the
+    /// ack of the request plus one in the kernel's replies to
+    /// PROC_CN_MCAST_LISTEN
+    pub fn ack(mut self, ack: u32) -> DatagramBuilder {
+        self.ack = ack;
+        self
+    }
+
+    /// Appends an event, fails for [`ProcEvent::Overflow`] which is
+    /// never sent by the kernel
+    pub fn event(mut self, event: &Event) -> Result<DatagramBuilder> {
+        let ev = proc_event_of(event)?;
+        let mut msg: cn_msg = unsafe { std::mem::zeroed() };
+        msg.id.idx = CN_IDX_PROC;
+        msg.id.val = binding::CN_VAL_PROC;
+        msg.seq = self.seq;
+        msg.ack = self.ack;
+        msg.len = size_of::<proc_event>() as u16;
+        let mut payload = bytes_of(&msg).to_vec();
+        payload.extend_from_slice(bytes_of(&ev));
+        self.push(binding::NLMSG_DONE, self.seq, &payload);
+        self.seq = self.seq.wrapping_add(1);
+        Ok(self)
+    }
+
+    /// Appends an NLMSG_NOOP, which readers skip
+    pub fn noop(mut self) -> DatagramBuilder {
+        self.push(binding::NLMSG_NOOP, 0, &[]);
+        self
+    }
+
+    /// Appends an NLMSG_ERROR carrying `errno`, 0 for a plain
+    /// acknowledgement
+    pub fn error(mut self, errno: i32) -> DatagramBuilder {
+        let mut err: nlmsgerr = unsafe { std::mem::zeroed() };
+        err.error = errno.wrapping_neg();
+        self.push(binding::NLMSG_ERROR, 0, bytes_of(&err));
+        self
+    }
+
+    /// Appends an NLMSG_OVERRUN
+    pub fn overrun(mut self) -> DatagramBuilder {
+        self.push(binding::NLMSG_OVERRUN, 0, &[]);
+        self
+    }
+
+    /// The datagram built so far
+    pub fn build(&self) -> Vec<u8> {
+        self.buf.clone()
+    }
+
+    /// Appends a netlink message, padded to NLMSG_ALIGNTO
+    fn push(&mut self, msg_type: u32, seq: u32, payload: &[u8]) {
+        let mut header: nlmsghdr = unsafe { std::mem::zeroed() };
+        header.nlmsg_len = nlmsg_length(payload.len()) as u32;
+        header.nlmsg_type = msg_type as u16;
+        header.nlmsg_seq = seq;
+        self.buf.extend_from_slice(bytes_of(&header));
+        self.buf.extend_from_slice(payload);
+        self.buf.resize(nlmsg_align(self.buf.len()), 0);
+    }
+}
+
+/// Encodes a single event as a datagram of its own, see
+/// [`DatagramBuilder`]
+pub fn encode(event: &Event) -> Result<Vec<u8>> {
+    Ok(DatagramBuilder::new().event(event)?.build())
+}
+
+fn proc_event_of(event: &Event) -> Result<proc_event> {
+    let mut ev: proc_event = unsafe { std::mem::zeroed() };
+    ev.cpu = event.cpu;
+    ev.timestamp_ns = event.timestamp_ns;
+    match event.kind {
+        ProcEvent::Ack { err } => {
+            ev.what = binding::PROC_EVENT_NONE;
+            ev.event_data.ack = proc_event__bindgen_ty_1__bindgen_ty_1 { err };
+        }
+        ProcEvent::Fork {
+            parent_pid,
+            parent_tgid,
+            child_pid,
+            child_tgid,
+        } => {
+            ev.what = binding::PROC_EVENT_FORK;
+            ev.event_data.fork = proc_event__bindgen_ty_1_fork_proc_event {
+                parent_pid,
+                parent_tgid,
+                child_pid,
+                child_tgid,
+            };
+        }
+        ProcEvent::Exec {
+            process_pid,
+            process_tgid,
+        } => {
+            ev.what = binding::PROC_EVENT_EXEC;
+            ev.event_data.exec = proc_event__bindgen_ty_1_exec_proc_event {
+                process_pid,
+                process_tgid,
+            };
+        }
+        ProcEvent::Uid {
+            process_pid,
+            process_tgid,
+            ruid,
+            euid,
+        } => {
+            ev.what = binding::PROC_EVENT_UID;
+            ev.event_data.id = proc_event__bindgen_ty_1_id_proc_event {
+                process_pid,
+                process_tgid,
+                r: proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_1 { ruid },
+                e: proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_2 { euid },
+            };
+        }
+        ProcEvent::Gid {
+            process_pid,
+            process_tgid,
+            rgid,
+            egid,
+        } => {
+            ev.what = binding::PROC_EVENT_GID;
+            ev.event_data.id = proc_event__bindgen_ty_1_id_proc_event {
+                process_pid,
+                process_tgid,
+                r: proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_1 { rgid },
+                e: proc_event__bindgen_ty_1_id_proc_event__bindgen_ty_2 { egid },
+            };
+        }
+        ProcEvent::Sid {
+            process_pid,
+            process_tgid,
+        } => {
+            ev.what = binding::PROC_EVENT_SID;
+            ev.event_data.sid = proc_event__bindgen_ty_1_sid_proc_event {
+                process_pid,
+                process_tgid,
+            };
+        }
+        ProcEvent::Ptrace {
+            process_pid,
+            process_tgid,
+            tracer_pid,
+            tracer_tgid,
+        } => {
+            ev.what = binding::PROC_EVENT_PTRACE;
+            ev.event_data.ptrace = proc_event__bindgen_ty_1_ptrace_proc_event {
+                process_pid,
+                process_tgid,
+                tracer_pid,
+                tracer_tgid,
+            };
+        }
+        ProcEvent::Comm {
+            process_pid,
+            process_tgid,
+            comm,
+        } => {
+            let mut raw = [0 as libc::c_char; TASK_COMM_LEN];
+            for (dst, src) in raw.iter_mut().zip(comm.raw().iter()) {
+                *dst = *src as libc::c_char;
+            }
+            ev.what = binding::PROC_EVENT_COMM;
+            ev.event_data.comm = proc_event__bindgen_ty_1_comm_proc_event {
+                process_pid,
+                process_tgid,
+                comm: raw,
+            };
+        }
+        ProcEvent::Coredump {
+            process_pid,
+            process_tgid,
+            parent_pid,
+            parent_tgid,
+        } => {
+            ev.what = binding::PROC_EVENT_COREDUMP;
+            ev.event_data.coredump = proc_event__bindgen_ty_1_coredump_proc_event {
+                process_pid,
+                process_tgid,
+                parent_pid,
+                parent_tgid,
+            };
+        }
+        ProcEvent::Exit {
+            process_pid,
+            process_tgid,
+            exit_code,
+            exit_signal,
+            parent_pid,
+            parent_tgid,
+        } => {
+            ev.what = binding::PROC_EVENT_EXIT;
+            ev.event_data.exit = proc_event__bindgen_ty_1_exit_proc_event {
+                process_pid,
+                process_tgid,
+                exit_code,
+                exit_signal,
+                parent_pid,
+                parent_tgid,
+            };
+        }
+        ProcEvent::Overflow { .. } => {
+            return Err(Error::Malformed {
+                reason: "overflows have no wire format",
+            })
+        }
+    }
+    Ok(ev)
+}
+
+/// Kernel structs that can be viewed as bytes
+///
+/// # Safety
+///
+/// Implementors must be `repr(C)` without padding, so that every byte
+/// of a value is initialised.
+unsafe trait Plain {}
+
+unsafe impl Plain for nlmsghdr {}
+unsafe impl Plain for nlmsgerr {}
+unsafe impl Plain for cn_msg {}
+unsafe impl Plain for proc_event {}
+
+fn bytes_of<T: Plain>(value: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn lays_out_like_the_kernel() {
+        let exit = Event {
+            cpu: 3,
+            timestamp_ns: 7,
+            kind: ProcEvent::Exit {
+                process_pid: 42,
+                process_tgid: 41,
+                exit_code: 256,
+                exit_signal: libc::SIGCHLD as u32,
+                parent_pid: 1,
+                parent_tgid: 1,
+            },
+        };
+        let buf = DatagramBuilder::new().seq(9).event(&exit).unwrap().build();
+        let word = |offset: usize| {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(&buf[offset..offset + 4]);
+            u32::from_ne_bytes(bytes)
+        };
+        // nlmsghdr, cn_msg and proc_event back to back
+        assert_eq!(buf.len(), 16 + 20 + 40);
+        assert_eq!(word(0), 76);
+        assert_eq!(word(8), 9);
+        assert_eq!(word(16), CN_IDX_PROC);
+        assert_eq!(word(24), 9);
+        assert_eq!(word(32) & 0xffff, 40);
+        assert_eq!(word(36), binding::PROC_EVENT_EXIT);
+        assert_eq!(word(40), 3);
+        assert_eq!(word(52), 42);
+        assert_eq!(word(60), 256);
+        assert_eq!(word(72), 1);
+    }
+
+    #[test]
+    fn builds_control_messages() {
+        let buf = DatagramBuilder::new().noop().error(0).build();
+        assert!(parse(&buf).unwrap().is_empty());
+        let buf = DatagramBuilder::new().error(libc::EPERM).build();
+        assert!(matches!(
+            parse(&buf),
+            Err(Error::Netlink { errno: libc::EPERM })
+        ));
+        let buf = DatagramBuilder::new().overrun().build();
+        assert!(matches!(parse(&buf), Err(Error::Overrun)));
+    }
+
+    #[test]
+    fn plain_structs_have_no_padding() {
+        assert_eq!(size_of::<nlmsghdr>(), 4 + 2 + 2 + 4 + 4);
+        assert_eq!(size_of::<nlmsgerr>(), 4 + size_of::<nlmsghdr>());
+        assert_eq!(size_of::<cn_msg>(), 4 + 4 + 4 + 4 + 2 + 2);
+        // what, cpu, timestamp_ns and the largest union member, exit
+        assert_eq!(size_of::<proc_event>(), 4 + 4 + 8 + 6 * 4);
+    }
+
+    #[test]
+    fn refuses_overflows() {
+        let overflow = Event {
+            cpu: 0,
+            timestamp_ns: 0,
+            kind: ProcEvent::Overflow {
+                count: 1,
+                processes: None,
+            },
+        };
+        assert!(encode(&overflow).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+// Errors returned by this crate
+
+use std::fmt;
+use std::io;
+
+/// Result type of this crate
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// What went wrong talking to the proc connector
+///
+/// More variants may be added as the crate grows, so matches need a
+/// wildcard arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Not allowed to open the socket or subscribe to events,
+    /// usually CAP_NET_ADMIN is missing
+    PermissionDenied { context: &'static str, errno: i32 },
+    /// The kernel lacks netlink, the connector or the feature asked for
+    Unsupported { context: &'static str, errno: i32 },
+    /// A datagram ended before the message it contains
+    ShortRead { expected: usize, actual: usize },
+    /// A message that doesn't follow the netlink or proc connector format
+    Malformed { reason: &'static str },
+    /// The kernel answered with an NLMSG_ERROR
+    Netlink { errno: i32 },
+    /// The kernel reported NLMSG_OVERRUN, messages were lost
+    Overrun,
+    /// No answer from the kernel in time
+    TimedOut { context: &'static str },
+    /// A blocking read was interrupted by a `Waker`
+    Woken,
+    /// Any other failed system call
+    Io {
+        context: &'static str,
+        source: io::Error,
+    },
+}
+
+impl Error {
+    /// Builds an error from `errno` after a failed system call
+    pub(crate) fn last_os_error(context: &'static str) -> Error {
+        Error::from_io(context, io::Error::last_os_error())
+    }
+
+    /// Builds an error from an errno value reported by the kernel
+    pub(crate) fn from_errno(context: &'static str, errno: i32) -> Error {
+        Error::from_io(context, io::Error::from_raw_os_error(errno))
+    }
+
+    fn from_io(context: &'static str, source: io::Error) -> Error {
+        match source.raw_os_error() {
+            Some(errno @ libc::EPERM) | Some(errno @ libc::EACCES) => {
+                Error::PermissionDenied { context, errno }
+            }
+            Some(errno @ libc::EPROTONOSUPPORT)
+            | Some(errno @ libc::EAFNOSUPPORT)
+            | Some(errno @ libc::ENOPROTOOPT)
+            | Some(errno @ libc::ENOSYS) => Error::Unsupported { context, errno },
+            _ => Error::Io { context, source },
+        }
+    }
+
+    /// The errno behind the error, if there is one
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match *self {
+            Error::PermissionDenied { errno, .. }
+            | Error::Unsupported { errno, .. }
+            | Error::Netlink { errno } => Some(errno),
+            Error::Io { ref source, .. } => source.raw_os_error(),
+            Error::ShortRead { .. }
+            | Error::Malformed { .. }
+            | Error::Overrun
+            | Error::TimedOut { .. }
+            | Error::Woken => None,
+        }
+    }
+
+    /// Whether a non-blocking read found nothing to read
+    pub fn is_would_block(&self) -> bool {
+        self.kind() == io::ErrorKind::WouldBlock
+    }
+
+    /// The `io::ErrorKind` this error converts to
+    pub fn kind(&self) -> io::ErrorKind {
+        match *self {
+            Error::PermissionDenied { .. } => io::ErrorKind::PermissionDenied,
+            Error::Unsupported { .. } => io::ErrorKind::Unsupported,
+            Error::ShortRead { .. } => io::ErrorKind::UnexpectedEof,
+            Error::Malformed { .. } => io::ErrorKind::InvalidData,
+            Error::Netlink { errno } => io::Error::from_raw_os_error(errno).kind(),
+            Error::Overrun => io::ErrorKind::Other,
+            Error::TimedOut { .. } => io::ErrorKind::TimedOut,
+            // not Interrupted, which callers retry
+            Error::Woken => io::ErrorKind::Other,
+            Error::Io { ref source, .. } => source.kind(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Error::PermissionDenied { context, errno } | Error::Unsupported { context, errno } => {
+                write!(f, "{}: {}", context, io::Error::from_raw_os_error(errno))
+            }
+            Error::ShortRead { expected, actual } => write!(
+                f,
+                "short read: message of {} bytes in {} bytes received",
+                expected, actual
+            ),
+            Error::Malformed { reason } => write!(f, "malformed message: {}", reason),
+            Error::Netlink { errno } => {
+                write!(f, "netlink error: {}", io::Error::from_raw_os_error(errno))
+            }
+            Error::Overrun => write!(f, "netlink overrun, messages were lost"),
+            Error::TimedOut { context } => write!(f, "{}: timed out", context),
+            Error::Woken => write!(f, "woken up by a waker"),
+            Error::Io {
+                context,
+                ref source,
+            } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Error::Io { ref source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> io::Error {
+        match err {
+            // keep the original error so raw_os_error still works
+            Error::Io { source, .. } => source,
+            err => io::Error::new(err.kind(), err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_errno() {
+        assert!(matches!(
+            Error::from_errno("bind", libc::EPERM),
+            Error::PermissionDenied {
+                context: "bind",
+                errno: libc::EPERM
+            }
+        ));
+        assert!(matches!(
+            Error::from_errno("socket", libc::EPROTONOSUPPORT),
+            Error::Unsupported { .. }
+        ));
+        let busy = Error::from_errno("bind", libc::EADDRINUSE);
+        assert_eq!(busy.raw_os_error(), Some(libc::EADDRINUSE));
+        assert_eq!(io::Error::from(busy).raw_os_error(), Some(libc::EADDRINUSE));
+        let denied = io::Error::from(Error::from_errno("listen", libc::EPERM));
+        assert_eq!(denied.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(io::Error::from(Error::Woken).kind(), io::ErrorKind::Other);
+    }
+}
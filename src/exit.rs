@@ -0,0 +1,143 @@
+// Decoding of the wait status word carried by PROC_EVENT_EXIT
+
+/// Why a process ended, decoded from the wait status the kernel
+/// reports in `exit_code`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The process called exit with this code
+    Exited(i32),
+    /// The process was killed by a signal
+    Signaled { signal: i32, core_dumped: bool },
+}
+
+impl ExitStatus {
+    /// Decodes a raw wait status as returned by `waitpid`
+    pub fn from_raw(status: u32) -> ExitStatus {
+        let status = status as libc::c_int;
+        if libc::WIFEXITED(status) {
+            ExitStatus::Exited(libc::WEXITSTATUS(status))
+        } else {
+            ExitStatus::Signaled {
+                signal: libc::WTERMSIG(status),
+                core_dumped: libc::WCOREDUMP(status),
+            }
+        }
+    }
+
+    /// Whether the process exited with code 0
+    pub fn success(&self) -> bool {
+        *self == ExitStatus::Exited(0)
+    }
+
+    /// The exit code, if the process exited normally
+    pub fn code(&self) -> Option<i32> {
+        match *self {
+            ExitStatus::Exited(code) => Some(code),
+            ExitStatus::Signaled { .. } => None,
+        }
+    }
+
+    /// The signal that killed the process, if any
+    pub fn signal(&self) -> Option<i32> {
+        match *self {
+            ExitStatus::Exited(_) => None,
+            ExitStatus::Signaled { signal, .. } => Some(signal),
+        }
+    }
+
+    /// The name of the signal that killed the process, e.g. `"SIGKILL"`
+    pub fn signal_name(&self) -> Option<&'static str> {
+        self.signal().and_then(signal_name)
+    }
+
+    /// Whether the process dumped core
+    pub fn core_dumped(&self) -> bool {
+        match *self {
+            ExitStatus::Exited(_) => false,
+            ExitStatus::Signaled { core_dumped, .. } => core_dumped,
+        }
+    }
+}
+
+impl std::fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            ExitStatus::Exited(code) => write!(f, "exit code: {}", code),
+            ExitStatus::Signaled {
+                signal,
+                core_dumped,
+            } => {
+                match signal_name(signal) {
+                    Some(name) => write!(f, "signal: {} ({})", signal, name)?,
+                    None => write!(f, "signal: {}", signal)?,
+                }
+                if core_dumped {
+                    write!(f, " (core dumped)")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn signal_name(signal: i32) -> Option<&'static str> {
+    let name = match signal {
+        libc::SIGHUP => "SIGHUP",
+        libc::SIGINT => "SIGINT",
+        libc::SIGQUIT => "SIGQUIT",
+        libc::SIGILL => "SIGILL",
+        libc::SIGTRAP => "SIGTRAP",
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGUSR1 => "SIGUSR1",
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGUSR2 => "SIGUSR2",
+        libc::SIGPIPE => "SIGPIPE",
+        libc::SIGALRM => "SIGALRM",
+        libc::SIGTERM => "SIGTERM",
+        libc::SIGCHLD => "SIGCHLD",
+        libc::SIGCONT => "SIGCONT",
+        libc::SIGSTOP => "SIGSTOP",
+        libc::SIGTSTP => "SIGTSTP",
+        libc::SIGTTIN => "SIGTTIN",
+        libc::SIGTTOU => "SIGTTOU",
+        libc::SIGURG => "SIGURG",
+        libc::SIGXCPU => "SIGXCPU",
+        libc::SIGXFSZ => "SIGXFSZ",
+        libc::SIGVTALRM => "SIGVTALRM",
+        libc::SIGPROF => "SIGPROF",
+        libc::SIGWINCH => "SIGWINCH",
+        libc::SIGIO => "SIGIO",
+        libc::SIGPWR => "SIGPWR",
+        libc::SIGSYS => "SIGSYS",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_wait_status() {
+        assert_eq!(ExitStatus::from_raw(0), ExitStatus::Exited(0));
+        assert!(ExitStatus::from_raw(0).success());
+        assert_eq!(ExitStatus::from_raw(3 << 8), ExitStatus::Exited(3));
+        let killed = ExitStatus::from_raw(libc::SIGKILL as u32);
+        assert_eq!(killed.signal(), Some(libc::SIGKILL));
+        assert_eq!(killed.signal_name(), Some("SIGKILL"));
+        assert!(!killed.core_dumped());
+        let segv = ExitStatus::from_raw(libc::SIGSEGV as u32 | 0x80);
+        assert_eq!(
+            segv,
+            ExitStatus::Signaled {
+                signal: libc::SIGSEGV,
+                core_dumped: true
+            }
+        );
+        assert_eq!(segv.to_string(), "signal: 11 (SIGSEGV) (core dumped)");
+    }
+}
@@ -0,0 +1,157 @@
+// Event type filtering, done by the kernel when it supports it
+// (Linux 6.6+) and in userspace otherwise
+
+use crate::binding;
+use crate::ProcEvent;
+
+/// A set of event types to receive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventMask(u32);
+
+impl EventMask {
+    pub const FORK: EventMask = EventMask(binding::PROC_EVENT_FORK);
+    pub const EXEC: EventMask = EventMask(binding::PROC_EVENT_EXEC);
+    pub const UID: EventMask = EventMask(binding::PROC_EVENT_UID);
+    pub const GID: EventMask = EventMask(binding::PROC_EVENT_GID);
+    pub const SID: EventMask = EventMask(binding::PROC_EVENT_SID);
+    pub const PTRACE: EventMask = EventMask(binding::PROC_EVENT_PTRACE);
+    pub const COMM: EventMask = EventMask(binding::PROC_EVENT_COMM);
+    /// Exits with a non-zero status only
+    pub const NONZERO_EXIT: EventMask = EventMask(binding::PROC_EVENT_NONZERO_EXIT);
+    pub const COREDUMP: EventMask = EventMask(binding::PROC_EVENT_COREDUMP);
+    /// All exits, regardless of status
+    pub const EXIT: EventMask = EventMask(binding::PROC_EVENT_EXIT);
+    /// Every event, including acknowledgements, the default
+    pub const ALL: EventMask = EventMask(
+        binding::PROC_EVENT_FORK
+            | binding::PROC_EVENT_EXEC
+            | binding::PROC_EVENT_UID
+            | binding::PROC_EVENT_GID
+            | binding::PROC_EVENT_SID
+            | binding::PROC_EVENT_PTRACE
+            | binding::PROC_EVENT_COMM
+            | binding::PROC_EVENT_NONZERO_EXIT
+            | binding::PROC_EVENT_COREDUMP
+            | binding::PROC_EVENT_EXIT,
+    );
+
+    /// The raw `proc_cn_event` bits
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Whether every type in `other` is also in `self`
+    pub fn contains(&self, other: EventMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether an event passes the mask, with the same rules the
+    /// kernel applies. Overflows always pass
+    pub fn matches(&self, event: &ProcEvent) -> bool {
+        if *self == EventMask::ALL {
+            return true;
+        }
+        let what = match *event {
+            ProcEvent::Overflow { .. } => return true,
+            ProcEvent::Ack { .. } => binding::PROC_EVENT_NONE,
+            ProcEvent::Fork { .. } => binding::PROC_EVENT_FORK,
+            ProcEvent::Exec { .. } => binding::PROC_EVENT_EXEC,
+            ProcEvent::Uid { .. } => binding::PROC_EVENT_UID,
+            ProcEvent::Gid { .. } => binding::PROC_EVENT_GID,
+            ProcEvent::Sid { .. } => binding::PROC_EVENT_SID,
+            ProcEvent::Ptrace { .. } => binding::PROC_EVENT_PTRACE,
+            ProcEvent::Comm { .. } => binding::PROC_EVENT_COMM,
+            ProcEvent::Coredump { .. } => binding::PROC_EVENT_COREDUMP,
+            ProcEvent::Exit { exit_code, .. } => {
+                if exit_code != 0 && self.contains(EventMask::NONZERO_EXIT) {
+                    return true;
+                }
+                binding::PROC_EVENT_EXIT
+            }
+        };
+        self.0 & what != 0
+    }
+}
+
+impl Default for EventMask {
+    fn default() -> EventMask {
+        EventMask::ALL
+    }
+}
+
+impl std::ops::BitOr for EventMask {
+    type Output = EventMask;
+
+    fn bitor(self, rhs: EventMask) -> EventMask {
+        EventMask(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for EventMask {
+    fn bitor_assign(&mut self, rhs: EventMask) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Whether the running kernel accepts a `proc_input` with an event
+/// type mask, which was added in Linux 6.6
+///
+/// This is a guess from the release in `uname`, the kernel offers no
+/// way to ask. Distribution kernels that backported `proc_input` are
+/// taken for older ones and filter in userspace, which only costs the
+/// wakeups for events that are dropped anyway.
+pub(crate) fn kernel_supports_event_mask() -> bool {
+    let mut uts = unsafe { std::mem::zeroed::<libc::utsname>() };
+    if unsafe { libc::uname(&mut uts) } < 0 {
+        return false;
+    }
+    let release = unsafe { std::ffi::CStr::from_ptr(uts.release.as_ptr()) };
+    match parse_release(&release.to_string_lossy()) {
+        Some(version) => version >= (6, 6),
+        None => false,
+    }
+}
+
+fn parse_release(release: &str) -> Option<(u32, u32)> {
+    let mut parts = release.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit(exit_code: u32) -> ProcEvent {
+        ProcEvent::Exit {
+            process_pid: 2,
+            process_tgid: 2,
+            exit_code,
+            exit_signal: 17,
+            parent_pid: 1,
+            parent_tgid: 1,
+        }
+    }
+
+    #[test]
+    fn matches_like_the_kernel() {
+        let nonzero = EventMask::NONZERO_EXIT;
+        assert!(!nonzero.matches(&exit(0)));
+        assert!(nonzero.matches(&exit(1 << 8)));
+        assert!((nonzero | EventMask::EXIT).matches(&exit(0)));
+
+        let fork = EventMask::FORK;
+        assert!(!fork.matches(&exit(0)));
+        assert!(!fork.matches(&ProcEvent::Ack { err: 0 }));
+        assert!(EventMask::ALL.matches(&ProcEvent::Ack { err: 0 }));
+    }
+
+    #[test]
+    fn parses_kernel_release() {
+        assert_eq!(parse_release("6.6.0"), Some((6, 6)));
+        assert_eq!(parse_release("5.15.0-91-generic"), Some((5, 15)));
+        assert_eq!(parse_release("6.18-rc1"), Some((6, 18)));
+        assert_eq!(parse_release("garbage"), None);
+    }
+}
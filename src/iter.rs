@@ -0,0 +1,128 @@
+// Blocking iterators over single events
+
+use std::collections::VecDeque;
+
+use crate::{recv_buffer, Event, PidMonitor, Result};
+
+/// Iterator over the events of a borrowed [`PidMonitor`], see
+/// [`PidMonitor::events`]
+///
+/// The receive buffer is reused for every datagram.
+#[derive(Debug)]
+pub struct Events<'a> {
+    monitor: &'a PidMonitor,
+    buffer: Vec<u8>,
+    buffered: VecDeque<Event>,
+}
+
+impl<'a> Events<'a> {
+    pub(crate) fn new(monitor: &'a PidMonitor) -> Events<'a> {
+        Events {
+            monitor,
+            buffer: recv_buffer(),
+            buffered: VecDeque::new(),
+        }
+    }
+}
+
+impl Iterator for Events<'_> {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Result<Event>> {
+        next_event(self.monitor, &mut self.buffer, &mut self.buffered)
+    }
+}
+
+/// Iterator over the events of an owned [`PidMonitor`], e.g. one
+/// moved into a consumer thread
+#[derive(Debug)]
+pub struct IntoEvents {
+    monitor: PidMonitor,
+    buffer: Vec<u8>,
+    buffered: VecDeque<Event>,
+}
+
+impl IntoEvents {
+    /// The monitor being iterated
+    pub fn monitor(&self) -> &PidMonitor {
+        &self.monitor
+    }
+
+    /// Gives the monitor back, dropping any buffered events
+    pub fn into_inner(self) -> PidMonitor {
+        self.monitor
+    }
+}
+
+impl Iterator for IntoEvents {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Result<Event>> {
+        next_event(&self.monitor, &mut self.buffer, &mut self.buffered)
+    }
+}
+
+impl IntoIterator for PidMonitor {
+    type Item = Result<Event>;
+    type IntoIter = IntoEvents;
+
+    fn into_iter(self) -> IntoEvents {
+        IntoEvents {
+            monitor: self,
+            buffer: recv_buffer(),
+            buffered: VecDeque::new(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a PidMonitor {
+    type Item = Result<Event>;
+    type IntoIter = Events<'a>;
+
+    fn into_iter(self) -> Events<'a> {
+        self.events()
+    }
+}
+
+fn next_event(
+    monitor: &PidMonitor,
+    buffer: &mut [u8],
+    buffered: &mut VecDeque<Event>,
+) -> Option<Result<Event>> {
+    loop {
+        if let Some(event) = buffered.pop_front() {
+            return Some(Ok(event));
+        }
+        if let Err(err) = monitor.read_events(buffer, |event| buffered.push_back(event)) {
+            return Some(Err(err));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Error, PidMonitor};
+
+    #[test]
+    fn nonblocking_iterators_would_block() {
+        let monitor = PidMonitor::builder().nonblocking(true).build().unwrap();
+        match monitor.events().next() {
+            Some(Err(err)) => assert!(err.is_would_block(), "{}", err),
+            other => panic!("{:?}", other),
+        }
+        match (&monitor).into_iter().next() {
+            Some(Err(err)) => assert!(err.is_would_block(), "{}", err),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn waker_interrupts_iteration() {
+        let mut monitor = PidMonitor::new().unwrap();
+        let id = monitor.id();
+        monitor.waker().unwrap().wake().unwrap();
+        let mut events = monitor.into_iter();
+        assert!(matches!(events.next(), Some(Err(Error::Woken))));
+        assert_eq!(events.into_inner().id(), id);
+    }
+}
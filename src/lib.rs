@@ -1,10 +1,46 @@
+#[cfg(feature = "async-io")]
+mod async_io;
+mod batch;
 mod binding;
+mod comm;
+mod encode;
+mod error;
+mod exit;
+mod filter;
+mod iter;
+#[cfg(feature = "mio")]
+mod mio;
+mod parse;
+mod procfs;
+mod sys;
+#[cfg(test)]
+mod testing;
+#[cfg(feature = "tokio")]
+mod tokio;
 use binding::{
-    cn_msg, nlmsghdr, proc_cn_mcast_op, sockaddr_nl, CN_IDX_PROC, NETLINK_CONNECTOR,
-    PROC_CN_MCAST_LISTEN,
+    cn_msg, nlmsghdr, proc_cn_mcast_op, proc_input, sockaddr_nl, CN_IDX_PROC, NETLINK_CONNECTOR,
+    PROC_CN_MCAST_IGNORE, PROC_CN_MCAST_LISTEN,
 };
-use libc;
-use std::io::{Error, Result};
+use parse::for_each_event;
+use std::convert::TryFrom;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+#[cfg(feature = "async-io")]
+pub use crate::async_io::AsyncIoPidMonitor;
+#[cfg(feature = "tokio")]
+pub use crate::tokio::AsyncPidMonitor;
+pub use batch::RecvBatch;
+pub use comm::{ProcessName, TASK_COMM_LEN};
+pub use encode::{encode, DatagramBuilder};
+pub use error::{Error, Result};
+pub use exit::ExitStatus;
+pub use filter::EventMask;
+pub use iter::{Events, IntoEvents};
+pub use parse::parse;
+pub use procfs::{scan_processes, ProcessInfo};
 
 // these are some macros defined in netlink.h
 
@@ -20,7 +56,24 @@ fn nlmsg_length(len: usize) -> usize {
     len + nlmsg_hdrlen()
 }
 
+/// The error the kernel reports for the subscription of port `id`, if
+/// `event` acknowledges it
+///
+/// The kernel acknowledges a multicast op with the `ack` it was sent
+/// plus one, and we send ours with the port id as `ack`.
+fn ack_of(id: u32, ack: u32, event: &Event) -> Option<u32> {
+    match event.kind {
+        ProcEvent::Ack { err } if ack == id.wrapping_add(1) => Some(err),
+        _ => None,
+    }
+}
+
 /// Events we are interested
+///
+/// This conflates fork with exec and exit with coredump, use
+/// [`ProcEvent`] instead, or [`PidEvent::from_proc_event`] while
+/// migrating
+#[deprecated(since = "0.2.0", note = "use `ProcEvent` instead")]
 #[derive(Debug)]
 pub enum PidEvent {
     /// New process, fork or exec
@@ -33,72 +86,629 @@ pub enum PidEvent {
     Exit(libc::c_int),
 }
 
+/// Every event reported by the proc connector, with all the fields
+/// the kernel sends for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcEvent {
+    /// Acknowledgement of a subscription request
+    /// PROC_EVENT_NONE
+    Ack { err: u32 },
+    /// A process or thread was created
+    /// PROC_EVENT_FORK
+    Fork {
+        parent_pid: libc::pid_t,
+        parent_tgid: libc::pid_t,
+        child_pid: libc::pid_t,
+        child_tgid: libc::pid_t,
+    },
+    /// A process called exec
+    /// PROC_EVENT_EXEC
+    Exec {
+        process_pid: libc::pid_t,
+        process_tgid: libc::pid_t,
+    },
+    /// Real or effective user id changed
+    /// PROC_EVENT_UID
+    Uid {
+        process_pid: libc::pid_t,
+        process_tgid: libc::pid_t,
+        ruid: u32,
+        euid: u32,
+    },
+    /// Real or effective group id changed
+    /// PROC_EVENT_GID
+    Gid {
+        process_pid: libc::pid_t,
+        process_tgid: libc::pid_t,
+        rgid: u32,
+        egid: u32,
+    },
+    /// A process became a session leader
+    /// PROC_EVENT_SID
+    Sid {
+        process_pid: libc::pid_t,
+        process_tgid: libc::pid_t,
+    },
+    /// A tracer attached to or detached from a process,
+    /// the tracer ids are 0 on detach
+    /// PROC_EVENT_PTRACE
+    Ptrace {
+        process_pid: libc::pid_t,
+        process_tgid: libc::pid_t,
+        tracer_pid: libc::pid_t,
+        tracer_tgid: libc::pid_t,
+    },
+    /// A process changed its name
+    /// PROC_EVENT_COMM
+    Comm {
+        process_pid: libc::pid_t,
+        process_tgid: libc::pid_t,
+        comm: ProcessName,
+    },
+    /// A process dumped core
+    /// PROC_EVENT_COREDUMP
+    Coredump {
+        process_pid: libc::pid_t,
+        process_tgid: libc::pid_t,
+        parent_pid: libc::pid_t,
+        parent_tgid: libc::pid_t,
+    },
+    /// A process or thread exited, `exit_code` is the raw wait status
+    /// (see [`ProcEvent::exit_status`]) and `exit_signal` the signal
+    /// sent to the parent. The parent ids are 0 on kernels older
+    /// than 4.18
+    /// PROC_EVENT_EXIT
+    Exit {
+        process_pid: libc::pid_t,
+        process_tgid: libc::pid_t,
+        exit_code: u32,
+        exit_signal: u32,
+        parent_pid: libc::pid_t,
+        parent_tgid: libc::pid_t,
+    },
+    /// The socket's receive buffer overflowed and events were lost,
+    /// only reported with [`PidMonitorBuilder::report_overflow`].
+    /// `count` is the number of overflows seen by the monitor so far,
+    /// `processes` a fresh /proc snapshot if
+    /// [`PidMonitorBuilder::rescan_on_overflow`] is set and /proc could
+    /// be read
+    Overflow {
+        count: u64,
+        processes: Option<Vec<ProcessInfo>>,
+    },
+}
+
+impl ProcEvent {
+    /// Id of the task the event is about, the child for fork events
+    pub fn pid(&self) -> Option<libc::pid_t> {
+        match *self {
+            ProcEvent::Ack { .. } | ProcEvent::Overflow { .. } => None,
+            ProcEvent::Fork { child_pid, .. } => Some(child_pid),
+            ProcEvent::Exec { process_pid, .. }
+            | ProcEvent::Uid { process_pid, .. }
+            | ProcEvent::Gid { process_pid, .. }
+            | ProcEvent::Sid { process_pid, .. }
+            | ProcEvent::Ptrace { process_pid, .. }
+            | ProcEvent::Comm { process_pid, .. }
+            | ProcEvent::Coredump { process_pid, .. }
+            | ProcEvent::Exit { process_pid, .. } => Some(process_pid),
+        }
+    }
+
+    /// Thread group id (the userspace pid) of the task the event
+    /// is about
+    pub fn tgid(&self) -> Option<libc::pid_t> {
+        match *self {
+            ProcEvent::Ack { .. } | ProcEvent::Overflow { .. } => None,
+            ProcEvent::Fork { child_tgid, .. } => Some(child_tgid),
+            ProcEvent::Exec { process_tgid, .. }
+            | ProcEvent::Uid { process_tgid, .. }
+            | ProcEvent::Gid { process_tgid, .. }
+            | ProcEvent::Sid { process_tgid, .. }
+            | ProcEvent::Ptrace { process_tgid, .. }
+            | ProcEvent::Comm { process_tgid, .. }
+            | ProcEvent::Coredump { process_tgid, .. }
+            | ProcEvent::Exit { process_tgid, .. } => Some(process_tgid),
+        }
+    }
+
+    /// Whether the event is about a thread other than the thread
+    /// group leader, e.g. a fork creating a thread or a thread exiting
+    pub fn is_thread(&self) -> bool {
+        match (self.pid(), self.tgid()) {
+            (Some(pid), Some(tgid)) => pid != tgid,
+            _ => false,
+        }
+    }
+
+    /// The decoded exit status of an exit event
+    pub fn exit_status(&self) -> Option<ExitStatus> {
+        match *self {
+            ProcEvent::Exit { exit_code, .. } => Some(ExitStatus::from_raw(exit_code)),
+            _ => None,
+        }
+    }
+}
+
+/// A [`ProcEvent`] together with the metadata the kernel sends
+/// along with every event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// CPU the event was generated on, for overflows the CPU that
+    /// noticed it, or 0 if that can't be told
+    pub cpu: u32,
+    /// CLOCK_MONOTONIC time of the event in nanoseconds, for overflows
+    /// the time it was noticed
+    pub timestamp_ns: u64,
+    /// The event itself
+    pub kind: ProcEvent,
+}
+
+impl Event {
+    /// Time passed since the kernel generated the event
+    pub fn age(&self) -> Duration {
+        monotonic_now().saturating_sub(Duration::from_nanos(self.timestamp_ns))
+    }
+
+    /// The event timestamp as an `Instant`, which on Linux uses the
+    /// same clock as the kernel
+    pub fn instant(&self) -> Instant {
+        let now = Instant::now();
+        now.checked_sub(self.age()).unwrap_or(now)
+    }
+
+    /// The event timestamp as wall clock time, this moves if the
+    /// system clock is changed after the event was generated
+    pub fn system_time(&self) -> SystemTime {
+        let now = SystemTime::now();
+        now.checked_sub(self.age()).unwrap_or(now)
+    }
+}
+
+fn monotonic_now() -> Duration {
+    let mut ts = unsafe { std::mem::zeroed::<libc::timespec>() };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+#[allow(deprecated)]
+impl PidEvent {
+    /// Maps a [`ProcEvent`] to the event the 0.1 `get_events` would
+    /// have returned for it, if any
+    pub fn from_proc_event(event: &ProcEvent) -> Option<PidEvent> {
+        match *event {
+            ProcEvent::Fork { child_pid, .. } => Some(PidEvent::New(child_pid)),
+            ProcEvent::Exec { process_pid, .. } => Some(PidEvent::New(process_pid)),
+            ProcEvent::Exit { process_pid, .. } | ProcEvent::Coredump { process_pid, .. } => {
+                Some(PidEvent::Exit(process_pid))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Options for creating a [`PidMonitor`]
+#[derive(Debug, Clone)]
+pub struct PidMonitorBuilder {
+    id: u32,
+    nonblocking: bool,
+    report_overflow: bool,
+    rescan_on_overflow: bool,
+    recv_buffer_size: Option<usize>,
+    force_recv_buffer_size: bool,
+    verify_credentials: bool,
+}
+
+impl PidMonitorBuilder {
+    /// Netlink port id to bind to, 0 by default to let the kernel
+    /// assign one that is free, see [`PidMonitor::id`]
+    pub fn id(mut self, id: u32) -> PidMonitorBuilder {
+        self.id = id;
+        self
+    }
+
+    /// Makes `get_events` fail with `WouldBlock` instead of waiting
+    /// when there is nothing to read, see [`PidMonitor::set_nonblocking`]
+    pub fn nonblocking(mut self, nonblocking: bool) -> PidMonitorBuilder {
+        self.nonblocking = nonblocking;
+        self
+    }
+
+    /// Reports receive buffer overflows as [`ProcEvent::Overflow`]
+    ///
+    /// By default the socket is set to NETLINK_NO_ENOBUFS, so events
+    /// dropped during bursts go unnoticed.
+    pub fn report_overflow(mut self, report: bool) -> PidMonitorBuilder {
+        self.report_overflow = report;
+        self
+    }
+
+    /// Attaches a /proc snapshot to every [`ProcEvent::Overflow`], so
+    /// consumers can resynchronise, implies `report_overflow`
+    pub fn rescan_on_overflow(mut self, rescan: bool) -> PidMonitorBuilder {
+        self.rescan_on_overflow = rescan;
+        self
+    }
+
+    /// Asks for a socket receive buffer of `size` bytes (SO_RCVBUF),
+    /// which the kernel caps at `net.core.rmem_max`
+    pub fn recv_buffer_size(mut self, size: usize) -> PidMonitorBuilder {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the receive buffer size with SO_RCVBUFFORCE, which ignores
+    /// `net.core.rmem_max`, when the process has CAP_NET_ADMIN. Without
+    /// it SO_RCVBUF is used as usual
+    pub fn force_recv_buffer_size(mut self, force: bool) -> PidMonitorBuilder {
+        self.force_recv_buffer_size = force;
+        self
+    }
+
+    /// Also checks the SCM_CREDENTIALS of every datagram, on top of
+    /// its sender address, and discards the ones not sent by the kernel
+    ///
+    /// This sets SO_PASSCRED on the socket. See
+    /// [`PidMonitor::spoofed_count`].
+    pub fn verify_credentials(mut self, verify: bool) -> PidMonitorBuilder {
+        self.verify_credentials = verify;
+        self
+    }
+
+    /// Creates the socket and binds it to the proc connector group
+    pub fn build(&self) -> Result<PidMonitor> {
+        let mut ty = libc::SOCK_DGRAM;
+        if self.nonblocking {
+            ty |= libc::SOCK_NONBLOCK;
+        }
+        let fd = sys::socket(
+            libc::PF_NETLINK,
+            ty,
+            // for some reason bindgen doesn't make this
+            // a libc::c_int
+            NETLINK_CONNECTOR as i32,
+        )?;
+        let mut nl = unsafe { std::mem::zeroed::<sockaddr_nl>() };
+        nl.nl_pid = self.id;
+        // Again this is an issue of bindgen vs libc
+        nl.nl_family = libc::AF_NETLINK as u16;
+        nl.nl_groups = CN_IDX_PROC;
+        sys::bind(fd.as_fd(), &nl)?;
+        // the kernel picks the port id if we asked for 0
+        let id = sys::getsockname(fd.as_fd())?.nl_pid;
+        let monitor = PidMonitor {
+            fd: Some(fd),
+            id,
+            ignore_threads: false,
+            nonblocking: AtomicBool::new(self.nonblocking),
+            listening: false,
+            event_mask: EventMask::ALL,
+            kernel_filtering: false,
+            waker: None,
+            report_overflow: self.report_overflow || self.rescan_on_overflow,
+            rescan_on_overflow: self.rescan_on_overflow,
+            overflows: AtomicU64::new(0),
+            verify_credentials: self.verify_credentials,
+            spoofed: AtomicU64::new(0),
+        };
+        if let Some(size) = self.recv_buffer_size {
+            monitor.set_recv_buffer_size(size, self.force_recv_buffer_size)?;
+        }
+        if self.verify_credentials {
+            monitor.setsockopt_int(
+                libc::SOL_SOCKET,
+                libc::SO_PASSCRED,
+                1,
+                "setsockopt SO_PASSCRED",
+            )?;
+        }
+        Ok(monitor)
+    }
+}
+
 /// Pid Monitor
 #[derive(Debug)]
 pub struct PidMonitor {
-    fd: libc::c_int,
+    // only None once the socket was moved out by `From<PidMonitor>`
+    fd: Option<OwnedFd>,
     id: u32,
+    ignore_threads: bool,
+    // O_NONBLOCK as last set through us, so reads don't need fcntl
+    nonblocking: AtomicBool,
+    listening: bool,
+    event_mask: EventMask,
+    kernel_filtering: bool,
+    waker: Option<Arc<OwnedFd>>,
+    report_overflow: bool,
+    rescan_on_overflow: bool,
+    overflows: AtomicU64,
+    verify_credentials: bool,
+    spoofed: AtomicU64,
 }
 
 impl PidMonitor {
     /// Creates a new PidMonitor
     pub fn new() -> Result<PidMonitor> {
-        PidMonitor::from_id(std::process::id())
+        PidMonitor::builder().build()
     }
 
-    /// Creates a new PidMonitor, the netlink socket will be created
-    /// with the given id instead of `std::process::id()`
+    /// Creates a new PidMonitor, the netlink socket will be bound to
+    /// the given port id instead of one assigned by the kernel
     pub fn from_id(id: u32) -> Result<PidMonitor> {
-        let fd = unsafe {
-            libc::socket(
-                libc::PF_NETLINK,
-                libc::SOCK_DGRAM,
-                // for some reason bindgen doesn't make this
-                // a libc::c_int
-                NETLINK_CONNECTOR as i32,
-            )
-        };
-        let mut nl = unsafe { std::mem::zeroed::<sockaddr_nl>() };
-        nl.nl_pid = id;
-        // Again this is an issue of bindgen vs libc
-        nl.nl_family = libc::AF_NETLINK as u16;
-        nl.nl_groups = CN_IDX_PROC;
+        PidMonitor::builder().id(id).build()
+    }
+
+    /// Options for creating a PidMonitor
+    pub fn builder() -> PidMonitorBuilder {
+        PidMonitorBuilder {
+            id: 0,
+            nonblocking: false,
+            report_overflow: false,
+            rescan_on_overflow: false,
+            recv_buffer_size: None,
+            force_recv_buffer_size: false,
+            verify_credentials: false,
+        }
+    }
+
+    /// Netlink port id the socket is bound to
+    ///
+    /// Unless one was given with [`PidMonitor::from_id`] this is the
+    /// one the kernel assigned, `std::process::id()` for the first
+    /// socket of the process and a unique negative number after that.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Changes the socket receive buffer size, see
+    /// [`PidMonitorBuilder::recv_buffer_size`] and
+    /// [`PidMonitorBuilder::force_recv_buffer_size`]
+    pub fn set_recv_buffer_size(&self, size: usize, force: bool) -> Result<()> {
+        let size = size.min(libc::c_int::MAX as usize) as libc::c_int;
+        if force {
+            match self.setsockopt_int(
+                libc::SOL_SOCKET,
+                libc::SO_RCVBUFFORCE,
+                size,
+                "setsockopt SO_RCVBUFFORCE",
+            ) {
+                // not privileged, do what we can
+                Err(Error::PermissionDenied { .. }) => {}
+                forced => return forced,
+            }
+        }
+        self.setsockopt_int(
+            libc::SOL_SOCKET,
+            libc::SO_RCVBUF,
+            size,
+            "setsockopt SO_RCVBUF",
+        )
+    }
+
+    /// The receive buffer size in effect, as reported by SO_RCVBUF
+    ///
+    /// The kernel doubles the requested size to leave room for its
+    /// bookkeeping, and this reports the doubled value.
+    pub fn recv_buffer_size(&self) -> Result<usize> {
+        let mut size: libc::c_int = 0;
+        let mut len = std::mem::size_of_val(&size) as libc::socklen_t;
         if unsafe {
-            libc::bind(
-                fd,
-                &nl as *const sockaddr_nl as _,
-                std::mem::size_of_val(&nl) as _,
+            libc::getsockopt(
+                self.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_RCVBUF,
+                &mut size as *mut libc::c_int as _,
+                &mut len,
             )
         } < 0
         {
-            return Err(Error::last_os_error());
+            return Err(Error::last_os_error("getsockopt SO_RCVBUF"));
         }
-        return Ok(PidMonitor { fd, id });
+        Ok(size as usize)
+    }
+
+    fn setsockopt_int(
+        &self,
+        level: libc::c_int,
+        name: libc::c_int,
+        val: libc::c_int,
+        context: &'static str,
+    ) -> Result<()> {
+        sys::setsockopt_int(self.as_fd(), level, name, val, context)
     }
 
+    /// Switches the socket between blocking and non-blocking mode
+    ///
+    /// In non-blocking mode `get_events` fails with an error of kind
+    /// `WouldBlock` (see [`Error::is_would_block`]) when no datagram is
+    /// queued, so the monitor can be polled together with other
+    /// descriptors. `listen` still waits for the acknowledgement.
+    ///
+    /// The mode is remembered by the monitor, so change it here rather
+    /// than with `fcntl` on the raw descriptor.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        let flags = unsafe { libc::fcntl(self.as_raw_fd(), libc::F_GETFL) };
+        if flags < 0 {
+            return Err(Error::last_os_error("fcntl F_GETFL"));
+        }
+        let flags = if nonblocking {
+            flags | libc::O_NONBLOCK
+        } else {
+            flags & !libc::O_NONBLOCK
+        };
+        if unsafe { libc::fcntl(self.as_raw_fd(), libc::F_SETFL, flags) } < 0 {
+            return Err(Error::last_os_error("fcntl F_SETFL"));
+        }
+        self.nonblocking.store(nonblocking, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// How long [`PidMonitor::listen`] waits for the kernel to
+    /// acknowledge the subscription
+    pub const LISTEN_TIMEOUT: Duration = Duration::from_secs(5);
+
     /// Signals to the kernel we are ready for listening to events
-    // TODO: We should really set this so than no ENOBUFS get sent
-    // our way
+    /// and waits for it to acknowledge the subscription
+    ///
+    /// Errors reported by the kernel, e.g. EPERM without
+    /// CAP_NET_ADMIN, are returned. The kernel doesn't answer at all
+    /// when the process is outside the initial pid or user namespace,
+    /// nor when it rejects a subscription with EPERM while nobody else
+    /// is listening, so this fails with [`Error::TimedOut`] after
+    /// [`PidMonitor::LISTEN_TIMEOUT`]. Events received before the
+    /// acknowledgement are dropped. Does nothing if the monitor is
+    /// already listening.
     pub fn listen(&mut self) -> Result<()> {
-        let val = true as libc::c_int;
-        if unsafe {
-            libc::setsockopt(
-                self.fd,
-                libc::SOL_NETLINK,
-                binding::NETLINK_NO_ENOBUFS as i32,
-                &val as *const libc::c_int as _,
-                std::mem::size_of_val(&val) as _,
-            )
-        } < 0
-        {
-            return Err(std::io::Error::last_os_error());
+        self.subscribe(Self::LISTEN_TIMEOUT)
+    }
+
+    /// Like [`PidMonitor::listen`] but waits `timeout` for the
+    /// acknowledgement instead of [`PidMonitor::LISTEN_TIMEOUT`]
+    pub fn listen_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.subscribe(timeout)
+    }
+
+    fn subscribe(&mut self, timeout: Duration) -> Result<()> {
+        // older kernels count every listen op, so subscribing twice
+        // would leave events enabled after we unsubscribe
+        if self.listening {
+            return Ok(());
+        }
+        self.setsockopt_int(
+            libc::SOL_NETLINK,
+            binding::NETLINK_NO_ENOBUFS as i32,
+            !self.report_overflow as libc::c_int,
+            "setsockopt NETLINK_NO_ENOBUFS",
+        )?;
+        self.send_mcast_op(PROC_CN_MCAST_LISTEN, None)?;
+        match self.wait_ack(timeout) {
+            Ok(0) => {}
+            Ok(err) => return Err(Error::from_errno("subscribe", err as i32)),
+            Err(err) => {
+                // the kernel may have counted us as a listener without
+                // the acknowledgement reaching us, and a retry would
+                // count us twice, so take the subscription back
+                let _ = self.send_mcast_op(PROC_CN_MCAST_IGNORE, None);
+                return Err(err);
+            }
+        }
+        self.listening = true;
+        // the kernel filters acknowledgements out as well, so the mask
+        // is only sent once we know the subscription went through
+        if self.event_mask != EventMask::ALL {
+            self.apply_event_mask()?;
+        }
+        Ok(())
+    }
+
+    /// Only receive the given event types
+    ///
+    /// On Linux 6.6 and later the kernel drops other events before
+    /// they reach the socket, on older kernels they are dropped by
+    /// [`PidMonitor::get_events`]. Which one applies is decided from
+    /// the kernel release, so a backport of the mask to an older kernel
+    /// goes unused. Can be called while listening.
+    ///
+    /// The kernel only acknowledges the change when the new mask is
+    /// [`EventMask::ALL`], that acknowledgement is waited for like in
+    /// [`PidMonitor::listen`] and events received before it are dropped.
+    pub fn set_event_mask(&mut self, mask: EventMask) -> Result<()> {
+        self.event_mask = mask;
+        if self.listening {
+            self.apply_event_mask()?;
+        }
+        Ok(())
+    }
+
+    /// The event types currently received
+    pub fn event_mask(&self) -> EventMask {
+        self.event_mask
+    }
+
+    /// Whether the kernel is filtering events for us, as opposed to
+    /// this being done in userspace
+    ///
+    /// Events are filtered in userspace either way, so a wrong guess
+    /// about the kernel never lets unwanted events through.
+    pub fn kernel_filtering(&self) -> bool {
+        self.kernel_filtering
+    }
+
+    fn apply_event_mask(&mut self) -> Result<()> {
+        if !filter::kernel_supports_event_mask() {
+            self.kernel_filtering = false;
+            return Ok(());
         }
+        // newer kernels keep one subscription per socket, so this
+        // updates the mask instead of counting another listener
+        self.send_mcast_op(PROC_CN_MCAST_LISTEN, Some(self.event_mask))?;
+        self.kernel_filtering = self.event_mask != EventMask::ALL;
+        // the acknowledgement goes through the new mask as well, so
+        // it only needs reading when the mask lets acks through
+        if self.event_mask.matches(&ProcEvent::Ack { err: 0 }) {
+            match self.wait_ack(Self::LISTEN_TIMEOUT)? {
+                0 => {}
+                err => return Err(Error::from_errno("set event mask", err as i32)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Tells the kernel we are no longer interested in events
+    ///
+    /// The kernel generates proc events system wide as long as anyone
+    /// is listening, so unsubscribe when events aren't needed. This is
+    /// done automatically when the monitor is dropped.
+    ///
+    /// Before Linux 6.6 the kernel keeps no per-socket subscription,
+    /// the socket stays in the multicast group and goes on receiving
+    /// events while any other process listens. Events read while the
+    /// monitor isn't listening are dropped, whichever kernel sent them,
+    /// including those still queued from before.
+    pub fn ignore(&mut self) -> Result<()> {
+        if !self.listening {
+            return Ok(());
+        }
+        // no acknowledgement to wait for, the kernel doesn't send one
+        // once the last listener is gone
+        self.send_mcast_op(PROC_CN_MCAST_IGNORE, None)?;
+        self.listening = false;
+        self.kernel_filtering = false;
+        Ok(())
+    }
+
+    /// Stops receiving events until [`PidMonitor::resume`] is called,
+    /// same as [`PidMonitor::ignore`]
+    pub fn pause(&mut self) -> Result<()> {
+        self.ignore()
+    }
+
+    /// Starts receiving events again after [`PidMonitor::pause`],
+    /// same as [`PidMonitor::listen`]
+    pub fn resume(&mut self) -> Result<()> {
+        self.listen()
+    }
+
+    /// Whether the monitor is currently subscribed to events
+    pub fn is_listening(&self) -> bool {
+        self.listening
+    }
+
+    /// Sends a multicast op to the proc connector, as a `proc_input`
+    /// if there is an event mask to go with it
+    fn send_mcast_op(&self, op: proc_cn_mcast_op, mask: Option<EventMask>) -> Result<()> {
+        let input = proc_input {
+            mcast_op: op,
+            event_type: mask.map_or(0, |mask| mask.bits()),
+        };
+        let payload_len = match mask {
+            Some(_) => std::mem::size_of::<proc_input>(),
+            None => std::mem::size_of::<proc_cn_mcast_op>(),
+        };
         let mut iov_vec = Vec::<libc::iovec>::new();
         // Set nlmsghdr
         let mut msghdr: nlmsghdr = unsafe { std::mem::zeroed() };
-        msghdr.nlmsg_len =
-            nlmsg_length(std::mem::size_of::<cn_msg>() + std::mem::size_of::<proc_cn_mcast_op>())
-                as u32;
+        msghdr.nlmsg_len = nlmsg_length(std::mem::size_of::<cn_msg>() + payload_len) as u32;
         msghdr.nlmsg_pid = self.id;
         //Another mismatch
         msghdr.nlmsg_type = binding::NLMSG_DONE as u16;
@@ -106,108 +716,717 @@ impl PidMonitor {
             iov_len: std::mem::size_of_val(&msghdr),
             iov_base: &msghdr as *const nlmsghdr as _,
         });
-        // Set cn_msg
+        // Set cn_msg, the kernel sends ack + 1 back in its
+        // acknowledgement, using our port id as ack tells ours apart
+        // from the ones other listeners get
         let mut cnmesg: cn_msg = unsafe { std::mem::zeroed() };
         cnmesg.id.idx = CN_IDX_PROC;
         cnmesg.id.val = binding::CN_VAL_PROC;
-        cnmesg.len = std::mem::size_of::<proc_cn_mcast_op>() as u16;
+        cnmesg.ack = self.id;
+        cnmesg.len = payload_len as u16;
         iov_vec.push(libc::iovec {
             iov_len: std::mem::size_of_val(&cnmesg),
             iov_base: &cnmesg as *const cn_msg as _,
         });
-        let op = PROC_CN_MCAST_LISTEN;
+        // mcast_op comes first in proc_input, so the legacy payload
+        // is just a shorter read of the same struct
         iov_vec.push(libc::iovec {
-            iov_len: std::mem::size_of_val(&op),
-            iov_base: &op as *const proc_cn_mcast_op as _,
+            iov_len: payload_len,
+            iov_base: &input as *const proc_input as _,
         });
-        if unsafe { libc::writev(self.fd, iov_vec.as_ptr() as _, 3) } < 0 {
-            Err(Error::last_os_error())
+        if unsafe { libc::writev(self.as_raw_fd(), iov_vec.as_ptr() as _, 3) } < 0 {
+            Err(Error::last_os_error("send"))
         } else {
             Ok(())
         }
     }
 
+    /// Reads messages until the acknowledgement of our last op arrives
+    /// and returns the error the kernel reports in it
+    fn wait_ack(&self, timeout: Duration) -> Result<u32> {
+        let deadline = Instant::now() + timeout;
+        let mut buffer = recv_buffer();
+        loop {
+            self.wait_readable(
+                Some(deadline),
+                "waiting for the subscription acknowledgement",
+            )?;
+            let mut ack_err = None;
+            let received = self.recv_msgs(&mut buffer, |ack, event| {
+                if let Some(err) = ack_of(self.id, ack, &event) {
+                    ack_err = Some(err);
+                }
+            });
+            match received {
+                // whatever was lost, it wasn't our acknowledgement
+                // unless it never comes, which the timeout covers
+                Err(err) if err.raw_os_error() == Some(libc::ENOBUFS) => continue,
+                received => received?,
+            }
+            if let Some(err) = ack_err {
+                return Ok(err);
+            }
+        }
+    }
+
+    /// Drops events about threads that are not thread group leaders
+    /// (see [`ProcEvent::is_thread`]) instead of returning them
+    pub fn set_ignore_threads(&mut self, ignore: bool) {
+        self.ignore_threads = ignore;
+    }
+
     /// Gets the next event or events comming the netlink socket
-    pub fn get_events(&self) -> Result<Vec<PidEvent>> {
-        let page_size = std::cmp::min(unsafe { libc::sysconf(libc::_SC_PAGE_SIZE) as usize }, 8192);
-        let mut buffer = Vec::<u32>::with_capacity(page_size);
-        let buff_size = buffer.capacity();
-        unsafe {
-            buffer.set_len(buff_size);
-        }
-        let len = unsafe { libc::recv(self.fd, buffer.as_mut_ptr() as _, buff_size * 4, 0) };
-        if len < 0 {
-            return Err(Error::last_os_error());
+    ///
+    /// Fails with [`Error::Woken`] if a [`Waker`] of this monitor is
+    /// woken while waiting.
+    pub fn get_events(&self) -> Result<Vec<Event>> {
+        let mut events = Vec::<Event>::new();
+        self.read_events(&mut recv_buffer(), |event| events.push(event))?;
+        Ok(events)
+    }
+
+    /// Like [`PidMonitor::get_events`] but fails with
+    /// [`Error::TimedOut`] if nothing arrives in time
+    pub fn get_events_timeout(&self, timeout: Duration) -> Result<Vec<Event>> {
+        self.wait_readable(Some(Instant::now() + timeout), "waiting for events")?;
+        let mut events = Vec::<Event>::new();
+        self.read_ready_events(&mut recv_buffer(), |event| events.push(event))?;
+        Ok(events)
+    }
+
+    /// A handle that interrupts blocking reads on this monitor from
+    /// another thread or a signal handler, e.g. to shut down cleanly
+    ///
+    /// All wakers of a monitor share one eventfd, which is polled
+    /// together with the socket once the first waker exists.
+    pub fn waker(&mut self) -> Result<Waker> {
+        if let Some(fd) = &self.waker {
+            return Ok(Waker { fd: fd.clone() });
         }
-        let mut header = buffer.as_ptr() as *const nlmsghdr;
-        let mut pidevents = Vec::<PidEvent>::new();
-        let mut len = len as usize;
-        loop {
-            // NLMSG_OK
-            if len < nlmsg_hdrlen() {
-                break;
+        let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+        if fd < 0 {
+            return Err(Error::last_os_error("eventfd"));
+        }
+        let fd = Arc::new(unsafe { OwnedFd::from_raw_fd(fd) });
+        self.waker = Some(fd.clone());
+        Ok(Waker { fd })
+    }
+
+    /// Waits until the socket is readable, a waker is woken or the
+    /// deadline passes
+    fn wait_readable(&self, deadline: Option<Instant>, context: &'static str) -> Result<()> {
+        let timeout_ms = match deadline {
+            Some(deadline) => {
+                let left = deadline.saturating_duration_since(Instant::now());
+                // round up so we don't spin on sub-millisecond waits
+                (left.as_nanos() as u64)
+                    .div_ceil(1_000_000)
+                    .min(i32::MAX as u64) as libc::c_int
             }
-            let msg_len = unsafe { (*header).nlmsg_len } as usize;
-            if len < msg_len {
-                break;
+            None => -1,
+        };
+        let mut pfds = [
+            libc::pollfd {
+                fd: self.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            },
+            libc::pollfd {
+                fd: self.waker.as_ref().map_or(-1, |fd| fd.as_raw_fd()),
+                events: libc::POLLIN,
+                revents: 0,
+            },
+        ];
+        let ready = unsafe { libc::poll(pfds.as_mut_ptr(), pfds.len() as _, timeout_ms) };
+        if ready < 0 {
+            return Err(Error::last_os_error("poll"));
+        }
+        if ready == 0 {
+            return Err(Error::TimedOut { context });
+        }
+        if pfds[1].revents != 0 {
+            // reset the counter so the next read waits again
+            let mut count = 0u64;
+            unsafe { libc::read(pfds[1].fd, &mut count as *mut u64 as _, 8) };
+            return Err(Error::Woken);
+        }
+        Ok(())
+    }
+
+    /// Iterates over events one at a time, waiting for more as needed
+    ///
+    /// The iterator never ends, in non-blocking mode it yields a
+    /// `WouldBlock` error when there is nothing to read.
+    pub fn events(&self) -> Events<'_> {
+        Events::new(self)
+    }
+
+    /// Receives one datagram into `buffer` and calls `f` for each
+    /// event in it that passes the event mask and thread filter
+    pub(crate) fn read_events(&self, buffer: &mut [u8], f: impl FnMut(Event)) -> Result<()> {
+        self.wait_for_events()?;
+        self.read_ready_events(buffer, f)
+    }
+
+    /// Waits for the socket or a waker before a blocking read
+    fn wait_for_events(&self) -> Result<()> {
+        // with nothing to wake us up recv can just block, and in
+        // non-blocking mode waiting is up to the caller
+        if self.waker.is_some() && !self.nonblocking.load(Ordering::Relaxed) {
+            self.wait_readable(None, "waiting for events")?;
+        }
+        Ok(())
+    }
+
+    fn read_ready_events(&self, buffer: &mut [u8], mut f: impl FnMut(Event)) -> Result<()> {
+        let received = self.recv_msgs(buffer, |_, event| {
+            if self.wants(&event) {
+                f(event)
             }
-            let msg_type = unsafe { (*header).nlmsg_type } as u32;
-            match msg_type {
-                binding::NLMSG_ERROR | binding::NLMSG_NOOP => continue,
-                _ => {
-                    if let Some(pidevent) = unsafe { parse_msg(header) } {
-                        pidevents.push(pidevent)
-                    }
-                }
-            };
-            // NLSMSG_NEXT
-            let aligned_len = nlmsg_align(msg_len);
-            header = (header as usize + aligned_len) as *const nlmsghdr;
-            len = match len.checked_sub(aligned_len) {
-                Some(v) => v,
-                None => break,
-            };
+        });
+        match received {
+            Err(err) if self.is_overflow(&err) => {
+                f(self.overflow_event());
+                Ok(())
+            }
+            received => received,
+        }
+    }
+
+    /// Whether an event arrived while subscribed and passes the event
+    /// mask and thread filter
+    fn wants(&self, event: &Event) -> bool {
+        self.listening
+            && self.event_mask.matches(&event.kind)
+            && !(self.ignore_threads && event.kind.is_thread())
+    }
+
+    /// Whether a failed read is an overflow to be reported as an event
+    fn is_overflow(&self, err: &Error) -> bool {
+        self.report_overflow && err.raw_os_error() == Some(libc::ENOBUFS)
+    }
+
+    /// Number of receive buffer overflows seen so far, only counted
+    /// with [`PidMonitorBuilder::report_overflow`]
+    pub fn overflow_count(&self) -> u64 {
+        self.overflows.load(Ordering::Relaxed)
+    }
+
+    fn overflow_event(&self) -> Event {
+        let count = self.overflows.fetch_add(1, Ordering::Relaxed) + 1;
+        // the overflow is counted already, so a failed scan still
+        // reports it, just without the snapshot
+        let processes = if self.rescan_on_overflow {
+            scan_processes().ok()
+        } else {
+            None
+        };
+        // sched_getcpu returns -1 where it isn't supported
+        let cpu = u32::try_from(unsafe { libc::sched_getcpu() }).unwrap_or(0);
+        Event {
+            cpu,
+            timestamp_ns: monotonic_now().as_nanos() as u64,
+            kind: ProcEvent::Overflow { count, processes },
+        }
+    }
+
+    /// Number of datagrams discarded so far because they were not sent
+    /// by the kernel
+    ///
+    /// A local process with CAP_NET_ADMIN can send datagrams to the
+    /// port of a connector socket that look just like the kernel's, so
+    /// every datagram's sender address is checked, and its credentials
+    /// too with [`PidMonitorBuilder::verify_credentials`]. Unprivileged
+    /// senders are already turned away by the kernel with EPERM.
+    pub fn spoofed_count(&self) -> u64 {
+        self.spoofed.load(Ordering::Relaxed)
+    }
+
+    /// Whether the datagram received with `sender` attached to `hdr`
+    /// comes from the kernel, counting it as spoofed if it doesn't
+    pub(crate) fn check_sender(&self, sender: &Sender, hdr: &libc::msghdr) -> bool {
+        let from_kernel = sender.is_kernel(hdr, self.verify_credentials);
+        if !from_kernel {
+            self.spoofed.fetch_add(1, Ordering::Relaxed);
         }
-        Ok(pidevents)
+        from_kernel
     }
+
+    /// Receives one datagram into `buffer` and calls `f` for each proc
+    /// event in it, unless it is spoofed
+    fn recv_msgs(&self, buffer: &mut [u8], f: impl FnMut(u32, Event)) -> Result<()> {
+        let mut iov = libc::iovec {
+            iov_base: buffer.as_mut_ptr() as _,
+            iov_len: std::mem::size_of_val(buffer),
+        };
+        let mut sender = Sender::new();
+        let mut hdr = unsafe { std::mem::zeroed::<libc::msghdr>() };
+        hdr.msg_iov = &mut iov;
+        hdr.msg_iovlen = 1;
+        sender.attach(&mut hdr, self.verify_credentials);
+        let len = unsafe { libc::recvmsg(self.as_raw_fd(), &mut hdr, 0) };
+        if len < 0 {
+            return Err(Error::last_os_error("recvmsg"));
+        }
+        if !self.check_sender(&sender, &hdr) {
+            return Ok(());
+        }
+        for_each_event(&buffer[..len as usize], f)
+    }
+}
+
+/// Room for the sender address and credentials of one datagram
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Sender {
+    addr: sockaddr_nl,
+    // CMSG_SPACE(sizeof(struct ucred)), u64 for the alignment of cmsghdr
+    control: [u64; 4],
 }
 
-unsafe fn parse_msg(header: *const nlmsghdr) -> Option<PidEvent> {
-    let msg = (header as usize + nlmsg_length(0)) as *const cn_msg;
-    if (*msg).id.idx != binding::CN_IDX_PROC || (*msg).id.val != binding::CN_VAL_PROC {
-        return None;
-    };
-    let proc_ev = (*msg).data.as_ptr() as *const binding::proc_event;
-    match (*proc_ev).what {
-        binding::PROC_EVENT_FORK => {
-            let pid = (*proc_ev).event_data.fork.child_pid;
-            Some(PidEvent::New(pid))
+impl Sender {
+    pub(crate) fn new() -> Sender {
+        Sender {
+            addr: unsafe { std::mem::zeroed() },
+            control: [0; 4],
+        }
+    }
+
+    /// Points the name, and control if `credentials`, of `hdr` here
+    pub(crate) fn attach(&mut self, hdr: &mut libc::msghdr, credentials: bool) {
+        hdr.msg_name = &mut self.addr as *mut sockaddr_nl as _;
+        hdr.msg_namelen = std::mem::size_of::<sockaddr_nl>() as _;
+        if credentials {
+            hdr.msg_control = self.control.as_mut_ptr() as _;
+            hdr.msg_controllen = std::mem::size_of_val(&self.control) as _;
         }
-        binding::PROC_EVENT_EXEC => {
-            let pid = (*proc_ev).event_data.exec.process_pid;
-            Some(PidEvent::New(pid))
+    }
+
+    /// Checks the address filled in by the kernel for `hdr` and, if
+    /// `credentials`, the SCM_CREDENTIALS message
+    fn is_kernel(&self, hdr: &libc::msghdr, credentials: bool) -> bool {
+        // user space sockets always have a non-zero port id
+        if hdr.msg_namelen as usize != std::mem::size_of::<sockaddr_nl>()
+            || self.addr.nl_family != libc::AF_NETLINK as u16
+            || self.addr.nl_pid != 0
+        {
+            return false;
         }
-        binding::PROC_EVENT_EXIT => {
-            let pid = (*proc_ev).event_data.exit.process_pid;
-            Some(PidEvent::Exit(pid))
+        if !credentials {
+            return true;
         }
-        binding::PROC_EVENT_COREDUMP => {
-            let pid = (*proc_ev).event_data.coredump.process_pid;
-            Some(PidEvent::Exit(pid))
+        let mut cmsg = unsafe { libc::CMSG_FIRSTHDR(hdr) };
+        while !cmsg.is_null() {
+            let header = unsafe { &*cmsg };
+            if header.cmsg_level == libc::SOL_SOCKET && header.cmsg_type == libc::SCM_CREDENTIALS {
+                let cred = unsafe {
+                    std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::ucred)
+                };
+                return cred.pid == 0 && cred.uid == 0;
+            }
+            cmsg = unsafe { libc::CMSG_NXTHDR(hdr, cmsg) };
         }
-        _ => None,
+        // SO_PASSCRED makes the kernel attach credentials to every
+        // datagram, so missing ones mean something is off
+        false
     }
 }
 
+/// Interrupts blocking reads of a [`PidMonitor`], see
+/// [`PidMonitor::waker`]
+#[derive(Debug, Clone)]
+pub struct Waker {
+    fd: Arc<OwnedFd>,
+}
+
+impl Waker {
+    /// Makes the current or next blocking read of the monitor fail
+    /// with [`Error::Woken`]
+    ///
+    /// Only writes to an eventfd, so it is async-signal-safe.
+    pub fn wake(&self) -> Result<()> {
+        let one = 1u64;
+        if unsafe { libc::write(self.fd.as_raw_fd(), &one as *const u64 as _, 8) } < 0 {
+            return Err(Error::last_os_error("eventfd write"));
+        }
+        Ok(())
+    }
+}
+
+/// A buffer big enough for any datagram the proc connector sends
+pub(crate) fn recv_buffer() -> Vec<u8> {
+    let page_size = std::cmp::min(unsafe { libc::sysconf(libc::_SC_PAGE_SIZE) as usize }, 8192);
+    vec![0; page_size]
+}
+
 impl Drop for PidMonitor {
     fn drop(&mut self) {
-        unsafe { libc::close(self.fd) };
+        if self.fd.is_some() {
+            let _ = self.ignore();
+        }
+    }
+}
+
+impl AsRawFd for PidMonitor {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_fd().as_raw_fd()
+    }
+}
+
+impl AsFd for PidMonitor {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd
+            .as_ref()
+            .expect("the socket is only taken by dropping the monitor")
+            .as_fd()
+    }
+}
+
+/// Hands the socket over without unsubscribing, the kernel keeps
+/// sending events to it until it is closed
+impl From<PidMonitor> for OwnedFd {
+    fn from(mut monitor: PidMonitor) -> OwnedFd {
+        monitor
+            .fd
+            .take()
+            .expect("the socket is only taken by dropping the monitor")
+    }
+}
+
+impl IntoRawFd for PidMonitor {
+    fn into_raw_fd(self) -> RawFd {
+        OwnedFd::from(self).into_raw_fd()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
     #[test]
     fn it_works() {}
+
+    #[test]
+    #[allow(deprecated)]
+    fn pid_event_from_proc_event() {
+        let fork = ProcEvent::Fork {
+            parent_pid: 1,
+            parent_tgid: 1,
+            child_pid: 42,
+            child_tgid: 42,
+        };
+        let coredump = ProcEvent::Coredump {
+            process_pid: 42,
+            process_tgid: 42,
+            parent_pid: 1,
+            parent_tgid: 1,
+        };
+        let sid = ProcEvent::Sid {
+            process_pid: 42,
+            process_tgid: 42,
+        };
+        assert!(matches!(
+            PidEvent::from_proc_event(&fork),
+            Some(PidEvent::New(42))
+        ));
+        assert!(matches!(
+            PidEvent::from_proc_event(&coredump),
+            Some(PidEvent::Exit(42))
+        ));
+        assert!(PidEvent::from_proc_event(&sid).is_none());
+    }
+
+    #[test]
+    fn thread_events() {
+        let thread = ProcEvent::Fork {
+            parent_pid: 10,
+            parent_tgid: 10,
+            child_pid: 11,
+            child_tgid: 10,
+        };
+        let process = ProcEvent::Fork {
+            parent_pid: 10,
+            parent_tgid: 10,
+            child_pid: 12,
+            child_tgid: 12,
+        };
+        assert_eq!(thread.pid(), Some(11));
+        assert_eq!(thread.tgid(), Some(10));
+        assert!(thread.is_thread());
+        assert!(!process.is_thread());
+        assert!(!ProcEvent::Ack { err: 0 }.is_thread());
+    }
+
+    #[test]
+    fn monitors_coexist() {
+        let first = PidMonitor::new().unwrap();
+        let second = PidMonitor::new().unwrap();
+        assert_ne!(first.id(), 0);
+        assert_ne!(first.id(), second.id());
+        let explicit = PidMonitor::from_id(std::process::id() | 0x4000_0000).unwrap();
+        assert_eq!(explicit.id(), std::process::id() | 0x4000_0000);
+        assert!(matches!(
+            PidMonitor::from_id(explicit.id()),
+            Err(Error::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn starts_unsubscribed() {
+        let mut monitor = PidMonitor::new().unwrap();
+        assert!(!monitor.is_listening());
+        monitor.ignore().unwrap();
+        monitor.pause().unwrap();
+        assert!(!monitor.is_listening());
+    }
+
+    #[test]
+    fn waker_interrupts_listen() {
+        let mut monitor = PidMonitor::new().unwrap();
+        monitor.waker().unwrap().wake().unwrap();
+        assert!(matches!(monitor.listen(), Err(Error::Woken)));
+        assert!(!monitor.is_listening());
+    }
+
+    #[test]
+    fn pauses_and_resumes() {
+        let mut monitor = PidMonitor::builder().nonblocking(true).build().unwrap();
+        // subscribing takes CAP_NET_ADMIN, and without other listeners
+        // the kernel doesn't even answer a rejected subscription
+        match monitor.listen_timeout(Duration::from_secs(1)) {
+            Err(Error::PermissionDenied { .. }) | Err(Error::TimedOut { .. }) => {
+                eprintln!("pauses_and_resumes: skipped, needs CAP_NET_ADMIN");
+                return;
+            }
+            listened => listened.unwrap(),
+        }
+        assert!(monitor.is_listening());
+
+        monitor.pause().unwrap();
+        assert!(!monitor.is_listening());
+        std::process::Command::new("true").status().unwrap();
+        assert!(testing::read_until_error(&monitor).is_would_block());
+
+        monitor.resume().unwrap();
+        assert!(monitor.is_listening());
+        let child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id() as libc::pid_t;
+        let _ = child.wait_with_output();
+        while !monitor
+            .get_events_timeout(Duration::from_secs(1))
+            .unwrap()
+            .iter()
+            .any(|event| event.kind.tgid() == Some(pid))
+        {}
+    }
+
+    #[test]
+    fn widening_the_mask_reads_the_ack() {
+        let mut monitor = PidMonitor::builder().nonblocking(true).build().unwrap();
+        match monitor.listen_timeout(Duration::from_secs(1)) {
+            Err(Error::PermissionDenied { .. }) | Err(Error::TimedOut { .. }) => {
+                eprintln!("widening_the_mask_reads_the_ack: skipped, needs CAP_NET_ADMIN");
+                return;
+            }
+            listened => listened.unwrap(),
+        }
+        monitor.set_event_mask(EventMask::EXEC).unwrap();
+        monitor.set_event_mask(EventMask::ALL).unwrap();
+        loop {
+            match monitor.get_events() {
+                Ok(events) => assert!(
+                    !events
+                        .iter()
+                        .any(|event| matches!(event.kind, ProcEvent::Ack { .. })),
+                    "{:?}",
+                    events
+                ),
+                Err(err) => {
+                    assert!(err.is_would_block(), "{}", err);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Whether the socket with inode `ino` is open in this process
+    fn socket_open(ino: libc::ino_t) -> bool {
+        let target = std::path::PathBuf::from(format!("socket:[{}]", ino));
+        std::fs::read_dir("/proc/self/fd")
+            .unwrap()
+            .filter_map(|entry| std::fs::read_link(entry.unwrap().path()).ok())
+            .any(|link| link == target)
+    }
+
+    #[test]
+    fn reports_socket_failure() {
+        sys::fail(sys::Call::Socket, libc::EMFILE);
+        let err = PidMonitor::new().unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EMFILE));
+        assert!(err.to_string().starts_with("socket: "));
+    }
+
+    #[test]
+    fn closes_socket_on_failure() {
+        for &(call, context) in &[
+            (sys::Call::Bind, "bind: "),
+            (sys::Call::GetSockName, "getsockname: "),
+            (sys::Call::SetSockOpt, "setsockopt SO_PASSCRED: "),
+        ] {
+            sys::fail(call, libc::EINVAL);
+            let err = PidMonitor::builder()
+                .verify_credentials(true)
+                .build()
+                .unwrap_err();
+            assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+            assert!(err.to_string().starts_with(context), "{}", err);
+            assert!(!socket_open(sys::last_socket().unwrap()), "{:?}", call);
+        }
+        let monitor = PidMonitor::new().unwrap();
+        assert!(socket_open(sys::last_socket().unwrap()));
+        drop(monitor);
+        assert!(!socket_open(sys::last_socket().unwrap()));
+    }
+
+    #[test]
+    fn into_raw_fd_keeps_the_socket() {
+        let mut monitor = PidMonitor::new().unwrap();
+        monitor.waker().unwrap();
+        let fd = monitor.into_raw_fd();
+        let socket = sys::last_socket().unwrap();
+        assert!(socket_open(socket));
+        drop(unsafe { OwnedFd::from_raw_fd(fd) });
+        assert!(!socket_open(socket));
+    }
+
+    #[test]
+    fn nonblocking_reads_would_block() {
+        let monitor = PidMonitor::builder().nonblocking(true).build().unwrap();
+        assert!(testing::read_until_error(&monitor).is_would_block());
+    }
+
+    #[test]
+    fn sets_recv_buffer_size() {
+        let monitor = PidMonitor::builder()
+            .recv_buffer_size(8192)
+            .build()
+            .unwrap();
+        assert_eq!(monitor.recv_buffer_size().unwrap(), 16384);
+        monitor.set_recv_buffer_size(16384, false).unwrap();
+        assert_eq!(monitor.recv_buffer_size().unwrap(), 32768);
+    }
+
+    #[test]
+    fn forced_recv_buffer_size_falls_back() {
+        // what SO_RCVBUFFORCE does without CAP_NET_ADMIN
+        sys::fail(sys::Call::SetSockOpt, libc::EPERM);
+        let monitor = PidMonitor::builder()
+            .recv_buffer_size(8192)
+            .force_recv_buffer_size(true)
+            .build()
+            .unwrap();
+        assert_eq!(monitor.recv_buffer_size().unwrap(), 16384);
+    }
+
+    #[test]
+    fn sockets_are_close_on_exec() {
+        let monitor = PidMonitor::new().unwrap();
+        let flags = unsafe { libc::fcntl(monitor.as_raw_fd(), libc::F_GETFD) };
+        assert_ne!(flags & libc::FD_CLOEXEC, 0);
+    }
+
+    #[test]
+    fn discards_spoofed_messages() {
+        let monitor = PidMonitor::builder()
+            .nonblocking(true)
+            .verify_credentials(true)
+            .build()
+            .unwrap();
+        let buf = encode(&testing::fork(42)).unwrap();
+
+        // sending to a connector port takes CAP_NET_ADMIN, which is
+        // exactly the kind of sender being guarded against
+        let sender =
+            sys::socket(libc::PF_NETLINK, libc::SOCK_DGRAM, NETLINK_CONNECTOR as i32).unwrap();
+        let mut nl = unsafe { std::mem::zeroed::<sockaddr_nl>() };
+        nl.nl_family = libc::AF_NETLINK as u16;
+        nl.nl_pid = monitor.id;
+        for _ in 0..2 {
+            let sent = unsafe {
+                libc::sendto(
+                    sender.as_raw_fd(),
+                    buf.as_ptr() as _,
+                    buf.len(),
+                    0,
+                    &nl as *const sockaddr_nl as _,
+                    std::mem::size_of_val(&nl) as _,
+                )
+            };
+            if sent < 0 && std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM) {
+                eprintln!("discards_spoofed_messages: skipped, needs CAP_NET_ADMIN");
+                return;
+            }
+            assert_eq!(sent, buf.len() as isize);
+        }
+
+        assert!(monitor.get_events().unwrap().is_empty());
+        assert_eq!(monitor.spoofed_count(), 1);
+        let mut batch = RecvBatch::new(4);
+        assert_eq!(monitor.recv_batch(&mut batch, |_| {}).unwrap(), 0);
+        assert_eq!(monitor.spoofed_count(), 2);
+    }
+
+    #[test]
+    fn ignores_acks_of_other_ports() {
+        let ack = |ack| {
+            let buf = DatagramBuilder::new()
+                .ack(ack)
+                .event(&Event {
+                    cpu: 0,
+                    timestamp_ns: 0,
+                    kind: ProcEvent::Ack {
+                        err: libc::EPERM as u32,
+                    },
+                })
+                .unwrap()
+                .build();
+            let mut acks = Vec::new();
+            for_each_event(&buf, |ack, event| acks.push(ack_of(42, ack, &event))).unwrap();
+            acks
+        };
+        assert_eq!(ack(43), [Some(libc::EPERM as u32)]);
+        assert_eq!(ack(42), [None]);
+        assert_eq!(ack(0), [None]);
+    }
+
+    #[test]
+    fn times_out_without_events() {
+        let monitor = PidMonitor::new().unwrap();
+        assert!(matches!(
+            monitor.get_events_timeout(Duration::from_millis(10)),
+            Err(Error::TimedOut { .. })
+        ));
+    }
+
+    #[test]
+    fn waker_interrupts_reads() {
+        let mut monitor = PidMonitor::new().unwrap();
+        monitor.waker().unwrap().wake().unwrap();
+        assert!(matches!(monitor.get_events(), Err(Error::Woken)));
+    }
+
+    #[test]
+    fn event_timestamp_conversion() {
+        let event = Event {
+            cpu: 0,
+            timestamp_ns: (monotonic_now() - Duration::from_secs(5)).as_nanos() as u64,
+            kind: ProcEvent::Ack { err: 0 },
+        };
+        let age = event.age();
+        assert!(age >= Duration::from_secs(5) && age < Duration::from_secs(6));
+        assert!(event.instant().elapsed() >= Duration::from_secs(5));
+        let since = SystemTime::now()
+            .duration_since(event.system_time())
+            .unwrap();
+        assert!(since >= Duration::from_secs(5));
+    }
 }
@@ -0,0 +1,57 @@
+// mio support, enabled by the `mio` feature
+
+use std::io;
+use std::os::unix::io::AsRawFd;
+
+use ::mio::event::Source;
+use ::mio::unix::SourceFd;
+use ::mio::{Interest, Registry, Token};
+
+use crate::PidMonitor;
+
+/// Registers the socket with a mio `Poll`, the monitor should be
+/// non-blocking (see [`PidMonitor::set_nonblocking`]) and read with
+/// `get_events` until it returns `WouldBlock`
+impl Source for PidMonitor {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        SourceFd(&self.as_raw_fd()).deregister(registry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::mio::{Events, Poll};
+    use std::time::Duration;
+
+    #[test]
+    fn registers_with_poll() {
+        let mut monitor = PidMonitor::builder().nonblocking(true).build().unwrap();
+        let mut poll = Poll::new().unwrap();
+        poll.registry()
+            .register(&mut monitor, Token(0), Interest::READABLE)
+            .unwrap();
+        let mut events = Events::with_capacity(4);
+        poll.poll(&mut events, Some(Duration::ZERO)).unwrap();
+        assert!(events.is_empty());
+        poll.registry().deregister(&mut monitor).unwrap();
+    }
+}
@@ -0,0 +1,395 @@
+// Decoding proc connector datagrams
+//
+// Works on plain byte slices and checks every length against the bytes
+// actually there, so it can be used on captured or fuzzed data as well
+// as on what the socket returns.
+
+use std::convert::TryInto;
+use std::mem::size_of;
+
+use crate::binding::{self, cn_msg, proc_event};
+use crate::{
+    nlmsg_align, nlmsg_hdrlen, Error, Event, ProcEvent, ProcessName, Result, TASK_COMM_LEN,
+};
+
+/// Decodes the proc events in a datagram received from the proc
+/// connector
+///
+/// Like reading from a [`PidMonitor`](crate::PidMonitor), control
+/// messages are skipped and NLMSG_ERROR and NLMSG_OVERRUN become
+/// errors, but no event mask or thread filter is applied.
+pub fn parse(datagram: &[u8]) -> Result<Vec<Event>> {
+    let mut events = Vec::new();
+    for_each_event(datagram, |_, event| events.push(event))?;
+    Ok(events)
+}
+
+/// Calls `f` with the cn_msg ack and the event of each proc event in
+/// `datagram`
+pub(crate) fn for_each_event(datagram: &[u8], mut f: impl FnMut(u32, Event)) -> Result<()> {
+    let mut rest = datagram;
+    while !rest.is_empty() {
+        if rest.len() < nlmsg_hdrlen() {
+            return Err(Error::ShortRead {
+                expected: nlmsg_hdrlen(),
+                actual: rest.len(),
+            });
+        }
+        let msg_len = u32_at(rest, 0) as usize;
+        if msg_len < nlmsg_hdrlen() {
+            return Err(Error::Malformed {
+                reason: "nlmsg_len shorter than the netlink header",
+            });
+        }
+        if rest.len() < msg_len {
+            return Err(Error::ShortRead {
+                expected: msg_len,
+                actual: rest.len(),
+            });
+        }
+        let payload = &rest[nlmsg_hdrlen()..msg_len];
+        match u16_at(rest, 4) as u32 {
+            binding::NLMSG_NOOP => {}
+            binding::NLMSG_ERROR => {
+                if payload.len() < size_of::<libc::c_int>() {
+                    return Err(Error::Malformed {
+                        reason: "NLMSG_ERROR too short for its error code",
+                    });
+                }
+                // nlmsgerr starts with the negated errno, 0 for a
+                // plain acknowledgement
+                let error = u32_at(payload, 0) as i32;
+                if error != 0 {
+                    return Err(Error::Netlink {
+                        errno: error.wrapping_neg(),
+                    });
+                }
+            }
+            binding::NLMSG_OVERRUN => return Err(Error::Overrun),
+            _ => {
+                if let Some((ack, event)) = parse_cn_msg(payload)? {
+                    f(ack, event)
+                }
+            }
+        }
+        // the padding of the last message may be missing
+        rest = rest.get(nlmsg_align(msg_len)..).unwrap_or(&[]);
+    }
+    Ok(())
+}
+
+/// Decodes a cn_msg, returning its ack and event if it is from the
+/// proc connector
+fn parse_cn_msg(msg: &[u8]) -> Result<Option<(u32, Event)>> {
+    if msg.len() < size_of::<cn_msg>() {
+        return Err(Error::Malformed {
+            reason: "message too short for a cn_msg",
+        });
+    }
+    // struct cn_msg { cb_id id; u32 seq; u32 ack; u16 len; u16 flags; }
+    let data_len = u16_at(msg, 16) as usize;
+    let data = match msg.get(size_of::<cn_msg>()..size_of::<cn_msg>() + data_len) {
+        Some(data) => data,
+        None => {
+            return Err(Error::Malformed {
+                reason: "cn_msg payload longer than its netlink message",
+            })
+        }
+    };
+    if u32_at(msg, 0) != binding::CN_IDX_PROC || u32_at(msg, 4) != binding::CN_VAL_PROC {
+        return Ok(None);
+    }
+    let ack = u32_at(msg, 12);
+    Ok(parse_proc_event(data)?.map(|event| (ack, event)))
+}
+
+/// Decodes a proc_event, `None` for kinds this crate doesn't know
+fn parse_proc_event(ev: &[u8]) -> Result<Option<Event>> {
+    if ev.len() < size_of::<proc_event>() {
+        return Err(Error::Malformed {
+            reason: "cn_msg payload too short for a proc_event",
+        });
+    }
+    // what, cpu and timestamp_ns come first, the event_data union at
+    // 16 and every member of it starts with the pid and tgid it is about
+    let process_pid = i32_at(ev, 16);
+    let process_tgid = i32_at(ev, 20);
+    let kind = match u32_at(ev, 0) {
+        binding::PROC_EVENT_NONE => ProcEvent::Ack {
+            err: u32_at(ev, 16),
+        },
+        binding::PROC_EVENT_FORK => ProcEvent::Fork {
+            parent_pid: process_pid,
+            parent_tgid: process_tgid,
+            child_pid: i32_at(ev, 24),
+            child_tgid: i32_at(ev, 28),
+        },
+        binding::PROC_EVENT_EXEC => ProcEvent::Exec {
+            process_pid,
+            process_tgid,
+        },
+        binding::PROC_EVENT_UID => ProcEvent::Uid {
+            process_pid,
+            process_tgid,
+            ruid: u32_at(ev, 24),
+            euid: u32_at(ev, 28),
+        },
+        binding::PROC_EVENT_GID => ProcEvent::Gid {
+            process_pid,
+            process_tgid,
+            rgid: u32_at(ev, 24),
+            egid: u32_at(ev, 28),
+        },
+        binding::PROC_EVENT_SID => ProcEvent::Sid {
+            process_pid,
+            process_tgid,
+        },
+        binding::PROC_EVENT_PTRACE => ProcEvent::Ptrace {
+            process_pid,
+            process_tgid,
+            tracer_pid: i32_at(ev, 24),
+            tracer_tgid: i32_at(ev, 28),
+        },
+        binding::PROC_EVENT_COMM => {
+            let mut comm = [0u8; TASK_COMM_LEN];
+            comm.copy_from_slice(&ev[24..24 + TASK_COMM_LEN]);
+            ProcEvent::Comm {
+                process_pid,
+                process_tgid,
+                comm: ProcessName::from_raw(comm),
+            }
+        }
+        binding::PROC_EVENT_COREDUMP => ProcEvent::Coredump {
+            process_pid,
+            process_tgid,
+            parent_pid: i32_at(ev, 24),
+            parent_tgid: i32_at(ev, 28),
+        },
+        binding::PROC_EVENT_EXIT => ProcEvent::Exit {
+            process_pid,
+            process_tgid,
+            exit_code: u32_at(ev, 24),
+            exit_signal: u32_at(ev, 28),
+            parent_pid: i32_at(ev, 32),
+            parent_tgid: i32_at(ev, 36),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(Event {
+        cpu: u32_at(ev, 4),
+        timestamp_ns: u64::from_ne_bytes(ev[8..16].try_into().unwrap()),
+        kind,
+    }))
+}
+
+// callers check the length first, these only ever see in bounds offsets
+
+fn u16_at(buf: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes(buf[offset..offset + 2].try_into().unwrap())
+}
+
+fn u32_at(buf: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+fn i32_at(buf: &[u8], offset: usize) -> i32 {
+    u32_at(buf, offset) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fork;
+    use crate::{encode, DatagramBuilder};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+
+    fn kinds(buf: &[u8]) -> Result<Vec<ProcEvent>> {
+        Ok(parse(buf)?.into_iter().map(|event| event.kind).collect())
+    }
+
+    #[test]
+    fn skips_control_messages() {
+        let buf = DatagramBuilder::new()
+            .noop()
+            .error(0)
+            .event(&fork(42))
+            .unwrap()
+            .build();
+        let events = kinds(&buf).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].pid(), Some(42));
+    }
+
+    #[test]
+    fn reports_control_errors() {
+        let buf = DatagramBuilder::new().error(libc::ENOBUFS).build();
+        assert!(matches!(
+            kinds(&buf),
+            Err(Error::Netlink {
+                errno: libc::ENOBUFS
+            })
+        ));
+
+        let buf = DatagramBuilder::new().overrun().build();
+        assert!(matches!(kinds(&buf), Err(Error::Overrun)));
+    }
+
+    #[test]
+    fn rejects_truncated_messages() {
+        let datagram = encode(&fork(42)).unwrap();
+        for len in 1..datagram.len() {
+            assert!(parse(&datagram[..len]).is_err(), "{} bytes", len);
+        }
+
+        // a cn_msg claiming more data than its netlink message holds,
+        // cn_msg.len is at 32
+        let mut buf = datagram.clone();
+        buf[32..34].copy_from_slice(&80u16.to_ne_bytes());
+        assert!(matches!(parse(&buf), Err(Error::Malformed { .. })));
+
+        // and a proc_event cut short inside it
+        let mut buf = datagram;
+        let len = nlmsg_hdrlen() + size_of::<cn_msg>() + 24;
+        buf.truncate(len);
+        buf[0..4].copy_from_slice(&(len as u32).to_ne_bytes());
+        buf[32..34].copy_from_slice(&24u16.to_ne_bytes());
+        assert!(matches!(parse(&buf), Err(Error::Malformed { .. })));
+    }
+
+    #[test]
+    fn ignores_other_connectors() {
+        let mut buf = encode(&fork(42)).unwrap();
+        buf[16..20].copy_from_slice(&(binding::CN_IDX_PROC + 1).to_ne_bytes());
+        assert!(parse(&buf).unwrap().is_empty());
+    }
+
+    fn any_pid() -> impl Strategy<Value = libc::pid_t> {
+        any::<libc::pid_t>()
+    }
+
+    fn any_kind() -> impl Strategy<Value = ProcEvent> {
+        prop_oneof![
+            any::<u32>().prop_map(|err| ProcEvent::Ack { err }),
+            (any_pid(), any_pid(), any_pid(), any_pid()).prop_map(|(a, b, c, d)| {
+                ProcEvent::Fork {
+                    parent_pid: a,
+                    parent_tgid: b,
+                    child_pid: c,
+                    child_tgid: d,
+                }
+            }),
+            (any_pid(), any_pid()).prop_map(|(a, b)| ProcEvent::Exec {
+                process_pid: a,
+                process_tgid: b,
+            }),
+            (any_pid(), any_pid(), any::<u32>(), any::<u32>()).prop_map(|(a, b, c, d)| {
+                ProcEvent::Uid {
+                    process_pid: a,
+                    process_tgid: b,
+                    ruid: c,
+                    euid: d,
+                }
+            }),
+            (any_pid(), any_pid(), any::<u32>(), any::<u32>()).prop_map(|(a, b, c, d)| {
+                ProcEvent::Gid {
+                    process_pid: a,
+                    process_tgid: b,
+                    rgid: c,
+                    egid: d,
+                }
+            }),
+            (any_pid(), any_pid()).prop_map(|(a, b)| ProcEvent::Sid {
+                process_pid: a,
+                process_tgid: b,
+            }),
+            (any_pid(), any_pid(), any_pid(), any_pid()).prop_map(|(a, b, c, d)| {
+                ProcEvent::Ptrace {
+                    process_pid: a,
+                    process_tgid: b,
+                    tracer_pid: c,
+                    tracer_tgid: d,
+                }
+            }),
+            (any_pid(), any_pid(), any::<[u8; TASK_COMM_LEN]>()).prop_map(|(a, b, c)| {
+                ProcEvent::Comm {
+                    process_pid: a,
+                    process_tgid: b,
+                    comm: ProcessName::from_raw(c),
+                }
+            }),
+            (any_pid(), any_pid(), any_pid(), any_pid()).prop_map(|(a, b, c, d)| {
+                ProcEvent::Coredump {
+                    process_pid: a,
+                    process_tgid: b,
+                    parent_pid: c,
+                    parent_tgid: d,
+                }
+            }),
+            (
+                any_pid(),
+                any_pid(),
+                any::<u32>(),
+                any::<u32>(),
+                any_pid(),
+                any_pid()
+            )
+                .prop_map(|(a, b, c, d, e, f)| ProcEvent::Exit {
+                    process_pid: a,
+                    process_tgid: b,
+                    exit_code: c,
+                    exit_signal: d,
+                    parent_pid: e,
+                    parent_tgid: f,
+                }),
+        ]
+    }
+
+    fn any_event() -> impl Strategy<Value = Event> {
+        (any::<u32>(), any::<u64>(), any_kind()).prop_map(|(cpu, timestamp_ns, kind)| Event {
+            cpu,
+            timestamp_ns,
+            kind,
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(events in vec(any_event(), 1..8), ack in any::<u32>()) {
+            let mut builder = DatagramBuilder::new().ack(ack);
+            for event in &events {
+                builder = builder.event(event).unwrap();
+            }
+            let buf = builder.build();
+            let mut parsed = Vec::new();
+            for_each_event(&buf, |a, event| parsed.push((a, event))).unwrap();
+            prop_assert_eq!(parsed, events.into_iter().map(|event| (ack, event)).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn rejects_any_truncation(event in any_event(), cut in any::<Index>()) {
+            let buf = encode(&event).unwrap();
+            let len = cut.index(buf.len() - 1) + 1;
+            prop_assert!(parse(&buf[..len]).is_err());
+        }
+
+        #[test]
+        fn survives_corruption(
+            event in any_event(),
+            flips in vec((any::<Index>(), any::<u8>()), 1..8),
+        ) {
+            let mut buf = encode(&event).unwrap();
+            for (index, byte) in flips {
+                let len = buf.len();
+                buf[index.index(len)] = byte;
+            }
+            let _ = parse(&buf);
+        }
+
+        #[test]
+        fn survives_arbitrary_bytes(bytes in vec(any::<u8>(), 0..512)) {
+            let _ = parse(&bytes);
+        }
+    }
+}
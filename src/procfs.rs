@@ -0,0 +1,83 @@
+// Snapshots of the running processes from /proc, to resynchronise
+// after events were lost
+
+use std::fs;
+
+use crate::{Error, ProcessName, Result, TASK_COMM_LEN};
+
+/// A process found in /proc
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: libc::pid_t,
+    pub ppid: libc::pid_t,
+    pub comm: ProcessName,
+}
+
+/// Lists the processes currently running, thread group leaders only
+///
+/// Processes that exit while /proc is being read are skipped.
+pub fn scan_processes() -> Result<Vec<ProcessInfo>> {
+    let entries = fs::read_dir("/proc").map_err(|err| Error::Io {
+        context: "reading /proc",
+        source: err,
+    })?;
+    let mut processes = Vec::new();
+    for entry in entries.flatten() {
+        let pid = match entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        {
+            Some(pid) => pid,
+            None => continue,
+        };
+        let stat = match fs::read(entry.path().join("stat")) {
+            Ok(stat) => stat,
+            Err(_) => continue,
+        };
+        if let Some(info) = parse_stat(pid, &stat) {
+            processes.push(info);
+        }
+    }
+    Ok(processes)
+}
+
+/// Parses `/proc/<pid>/stat`, whose comm field is in parentheses and
+/// may contain anything, including spaces and parentheses
+fn parse_stat(pid: libc::pid_t, stat: &[u8]) -> Option<ProcessInfo> {
+    let open = stat.iter().position(|&b| b == b'(')?;
+    let close = stat.iter().rposition(|&b| b == b')')?;
+    let name = stat.get(open + 1..close)?;
+    let mut comm = [0u8; TASK_COMM_LEN];
+    let len = name.len().min(TASK_COMM_LEN - 1);
+    comm[..len].copy_from_slice(&name[..len]);
+    // after the comm come the state and the parent pid
+    let rest = std::str::from_utf8(stat.get(close + 1..)?).ok()?;
+    let ppid = rest.split_whitespace().nth(1)?.parse().ok()?;
+    Some(ProcessInfo {
+        pid,
+        ppid,
+        comm: ProcessName::from_raw(comm),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stat() {
+        let info = parse_stat(42, b"42 (my (odd) name) S 7 42 42 0 -1").unwrap();
+        assert_eq!(info.pid, 42);
+        assert_eq!(info.ppid, 7);
+        assert_eq!(info.comm.to_string_lossy(), "my (odd) name");
+        assert!(parse_stat(42, b"garbage").is_none());
+    }
+
+    #[test]
+    fn finds_ourselves() {
+        let pid = std::process::id() as libc::pid_t;
+        let processes = scan_processes().unwrap();
+        assert!(processes.iter().any(|p| p.pid == pid));
+    }
+}
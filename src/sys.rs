@@ -0,0 +1,132 @@
+// Thin wrappers over the socket system calls a PidMonitor is built with
+//
+// Each one turns a failure into an Error right away, and hands out file
+// descriptors as OwnedFd so they are closed on every error path. Tests
+// can make the next call of a kind fail with `fail`.
+
+use std::os::unix::io::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+
+use crate::binding::sockaddr_nl;
+use crate::{Error, Result};
+
+/// The system calls that can be made to fail in tests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Call {
+    Socket,
+    Bind,
+    GetSockName,
+    SetSockOpt,
+}
+
+#[cfg(test)]
+thread_local! {
+    static FAULTS: std::cell::RefCell<Vec<(Call, libc::c_int)>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+    static LAST_SOCKET: std::cell::Cell<Option<libc::ino_t>> = const { std::cell::Cell::new(None) };
+}
+
+/// Makes the next `call` on this thread fail with `errno`
+#[cfg(test)]
+pub(crate) fn fail(call: Call, errno: libc::c_int) {
+    FAULTS.with(|faults| faults.borrow_mut().push((call, errno)));
+}
+
+#[cfg(test)]
+fn injected(call: Call) -> Option<libc::c_int> {
+    FAULTS.with(|faults| {
+        let mut faults = faults.borrow_mut();
+        let index = faults.iter().position(|&(c, _)| c == call)?;
+        Some(faults.remove(index).1)
+    })
+}
+
+/// Inode of the last socket created on this thread, to tell whether
+/// it is still open from /proc/self/fd
+#[cfg(test)]
+pub(crate) fn last_socket() -> Option<libc::ino_t> {
+    LAST_SOCKET.with(|last| last.get())
+}
+
+#[cfg(not(test))]
+#[inline(always)]
+fn injected(_call: Call) -> Option<libc::c_int> {
+    None
+}
+
+pub(crate) fn socket(
+    domain: libc::c_int,
+    ty: libc::c_int,
+    protocol: libc::c_int,
+) -> Result<OwnedFd> {
+    if let Some(errno) = injected(Call::Socket) {
+        return Err(Error::from_errno("socket", errno));
+    }
+    let fd = unsafe { libc::socket(domain, ty | libc::SOCK_CLOEXEC, protocol) };
+    if fd < 0 {
+        return Err(Error::last_os_error("socket"));
+    }
+    #[cfg(test)]
+    {
+        let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+        if unsafe { libc::fstat(fd, &mut stat) } == 0 {
+            LAST_SOCKET.with(|last| last.set(Some(stat.st_ino)));
+        }
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+pub(crate) fn bind(fd: BorrowedFd<'_>, addr: &sockaddr_nl) -> Result<()> {
+    if let Some(errno) = injected(Call::Bind) {
+        return Err(Error::from_errno("bind", errno));
+    }
+    if unsafe {
+        libc::bind(
+            fd.as_raw_fd(),
+            addr as *const sockaddr_nl as _,
+            std::mem::size_of_val(addr) as _,
+        )
+    } < 0
+    {
+        return Err(Error::last_os_error("bind"));
+    }
+    Ok(())
+}
+
+pub(crate) fn getsockname(fd: BorrowedFd<'_>) -> Result<sockaddr_nl> {
+    if let Some(errno) = injected(Call::GetSockName) {
+        return Err(Error::from_errno("getsockname", errno));
+    }
+    let mut addr = unsafe { std::mem::zeroed::<sockaddr_nl>() };
+    let mut len = std::mem::size_of_val(&addr) as libc::socklen_t;
+    if unsafe { libc::getsockname(fd.as_raw_fd(), &mut addr as *mut sockaddr_nl as _, &mut len) }
+        < 0
+    {
+        return Err(Error::last_os_error("getsockname"));
+    }
+    Ok(addr)
+}
+
+pub(crate) fn setsockopt_int(
+    fd: BorrowedFd<'_>,
+    level: libc::c_int,
+    name: libc::c_int,
+    val: libc::c_int,
+    context: &'static str,
+) -> Result<()> {
+    if let Some(errno) = injected(Call::SetSockOpt) {
+        return Err(Error::from_errno(context, errno));
+    }
+    if unsafe {
+        libc::setsockopt(
+            fd.as_raw_fd(),
+            level,
+            name,
+            &val as *const libc::c_int as _,
+            std::mem::size_of_val(&val) as _,
+        )
+    } < 0
+    {
+        return Err(Error::last_os_error(context));
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+// Fixtures shared by the unit tests of several modules
+
+use crate::{Error, Event, PidMonitor, ProcEvent};
+
+/// Init forking `child_pid`, a single threaded process
+pub(crate) fn fork(child_pid: libc::pid_t) -> Event {
+    Event {
+        cpu: 0,
+        timestamp_ns: 0,
+        kind: ProcEvent::Fork {
+            parent_pid: 1,
+            parent_tgid: 1,
+            child_pid,
+            child_tgid: child_pid,
+        },
+    }
+}
+
+/// Reads `monitor` until it fails and returns the error
+///
+/// Datagrams sent to other listeners reach a socket that never
+/// subscribed as well, they are read and dropped here.
+pub(crate) fn read_until_error(monitor: &PidMonitor) -> Error {
+    loop {
+        match monitor.get_events() {
+            Ok(events) => assert!(events.is_empty(), "{:?}", events),
+            Err(err) => return err,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+// Async support for tokio, enabled by the `tokio` feature
+
+use std::collections::VecDeque;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use ::tokio::io::unix::AsyncFd;
+use futures_core::Stream;
+
+use crate::{Event, PidMonitor, Result};
+
+/// A [`PidMonitor`] driven by the tokio reactor
+///
+/// Yields events one at a time as a `Stream`. A datagram can carry
+/// several events, the ones not yielded yet wait in a queue of this
+/// wrapper, so cancelling `next_event()` loses nothing but
+/// [`AsyncPidMonitor::into_inner`] drops them.
+///
+/// ```no_run
+/// # async fn run() -> cnproc::Result<()> {
+/// use cnproc::{AsyncPidMonitor, PidMonitor};
+///
+/// let mut monitor = PidMonitor::new()?;
+/// monitor.listen()?;
+/// let mut monitor = AsyncPidMonitor::new(monitor)?;
+/// loop {
+///     let event = monitor.next_event().await?;
+///     println!("{:?}", event.kind);
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncPidMonitor {
+    inner: AsyncFd<PidMonitor>,
+    buffered: VecDeque<Event>,
+}
+
+impl AsyncPidMonitor {
+    /// Switches `monitor` to non-blocking mode and registers it with
+    /// the current tokio runtime
+    ///
+    /// # Panics
+    ///
+    /// When called outside a tokio runtime, or in one without IO
+    /// enabled.
+    pub fn new(monitor: PidMonitor) -> Result<AsyncPidMonitor> {
+        monitor.set_nonblocking(true)?;
+        let inner = AsyncFd::new(monitor).map_err(|err| crate::Error::Io {
+            context: "registering with tokio",
+            source: err,
+        })?;
+        Ok(AsyncPidMonitor {
+            inner,
+            buffered: VecDeque::new(),
+        })
+    }
+
+    /// The wrapped monitor
+    pub fn get_ref(&self) -> &PidMonitor {
+        self.inner.get_ref()
+    }
+
+    /// The wrapped monitor, e.g. to change its event mask
+    pub fn get_mut(&mut self) -> &mut PidMonitor {
+        self.inner.get_mut()
+    }
+
+    /// Deregisters the monitor and gives it back, still non-blocking,
+    /// dropping any buffered events
+    pub fn into_inner(self) -> PidMonitor {
+        self.inner.into_inner()
+    }
+
+    /// Waits for the next event
+    pub async fn next_event(&mut self) -> Result<Event> {
+        poll_fn(|cx| self.poll_event(cx)).await
+    }
+
+    /// Polls for the next event
+    pub fn poll_event(&mut self, cx: &mut Context<'_>) -> Poll<Result<Event>> {
+        loop {
+            if let Some(event) = self.buffered.pop_front() {
+                return Poll::Ready(Ok(event));
+            }
+            let mut guard = match self.inner.poll_read_ready(cx) {
+                Poll::Ready(Ok(guard)) => guard,
+                Poll::Ready(Err(err)) => {
+                    return Poll::Ready(Err(crate::Error::Io {
+                        context: "polling with tokio",
+                        source: err,
+                    }))
+                }
+                Poll::Pending => return Poll::Pending,
+            };
+            match guard.get_inner().get_events() {
+                Ok(events) => self.buffered.extend(events),
+                Err(err) if err.is_would_block() => guard.clear_ready(),
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+    }
+}
+
+impl Stream for AsyncPidMonitor {
+    type Item = Result<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Event>>> {
+        self.get_mut().poll_event(cx).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn idle_monitor_is_pending() {
+        let mut monitor = AsyncPidMonitor::new(PidMonitor::new().unwrap()).unwrap();
+        poll_fn(|cx| {
+            assert!(monitor.poll_event(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        let monitor = monitor.into_inner();
+        assert!(crate::testing::read_until_error(&monitor).is_would_block());
+    }
+}